@@ -0,0 +1,303 @@
+//! Custom emoji that are not part of Unicode, e.g. `:partyparrot:` or a company logo, registered by applications.
+//!
+//! A [`Registry`] holds the custom emoji of a workspace. They are drawn from an image rather than a character, so
+//! [`Registry::expand`] splits text into [`Segment`]s instead of returning a string, and they form their own group
+//! next to the Unicode [`Group`](unicode_types::Group)s.
+
+use std::{collections::HashMap, fmt};
+
+use unicode_types::fold;
+
+use crate::{
+    StaticEmoji, by_shortcode,
+    search::{self, Match, OwnedTokenTables, TokenTablesBuilder},
+    shortcodes,
+};
+
+/// A custom emoji.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct CustomEmoji {
+    /// The shortcode, without colons, e.g. `"partyparrot"`.
+    pub shortcode: String,
+    pub name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
+    /// Path or URL of the image.
+    pub image: String,
+    /// A category within the custom emoji, e.g. `"Company"`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub category: Option<String>,
+}
+
+/// Why a [`CustomEmoji`] could not be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterError {
+    /// The shortcode is not made of lowercase ASCII letters, digits, `_`, `+` and `-`.
+    InvalidShortcode(String),
+    /// The shortcode already belongs to a Unicode emoji.
+    UnicodeShortcode(String),
+    /// The registry holds 65 536 emoji already.
+    Full,
+}
+
+impl fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidShortcode(shortcode) => write!(f, "invalid shortcode ':{shortcode}:'"),
+            Self::UnicodeShortcode(shortcode) => write!(f, "':{shortcode}:' is already the shortcode of a Unicode emoji"),
+            Self::Full => write!(f, "too many custom emoji"),
+        }
+    }
+}
+
+impl std::error::Error for RegisterError {}
+
+/// A part of text expanded by [`Registry::expand`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'t, 'r> {
+    Text(&'t str),
+    Emoji(&'static StaticEmoji),
+    Custom(&'r CustomEmoji),
+}
+
+/// The custom emoji of a workspace, looked up by shortcode and searchable like [`crate::search`].
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    emojis: Vec<CustomEmoji>,
+    by_shortcode: HashMap<String, usize>,
+    tokens: OwnedTokenTables,
+}
+
+impl Registry {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `emoji`, returning the custom emoji it replaces when its shortcode was already registered.
+    pub fn register(&mut self, emoji: CustomEmoji) -> Result<Option<CustomEmoji>, RegisterError> {
+        let previous = self.insert(emoji)?;
+        self.index_tokens();
+        Ok(previous)
+    }
+
+    /// Adds `emojis` like [`Registry::register`], returning why those that were skipped could not be registered.
+    ///
+    /// The search index is rebuilt once rather than after every emoji, so prefer it for loading many emoji.
+    pub fn extend(&mut self, emojis: impl IntoIterator<Item = CustomEmoji>) -> Vec<RegisterError> {
+        let errors = emojis.into_iter().filter_map(|emoji| self.insert(emoji).err()).collect();
+        self.index_tokens();
+        errors
+    }
+
+    /// Adds `emoji` without updating the search index.
+    fn insert(&mut self, emoji: CustomEmoji) -> Result<Option<CustomEmoji>, RegisterError> {
+        if !shortcodes::is_shortcode(&emoji.shortcode) {
+            return Err(RegisterError::InvalidShortcode(emoji.shortcode));
+        }
+        if by_shortcode(&emoji.shortcode).is_some() {
+            return Err(RegisterError::UnicodeShortcode(emoji.shortcode));
+        }
+
+        let previous = if let Some(&i) = self.by_shortcode.get(&emoji.shortcode) {
+            Some(std::mem::replace(&mut self.emojis[i], emoji))
+        } else if self.emojis.len() > usize::from(u16::MAX) {
+            return Err(RegisterError::Full);
+        } else {
+            self.by_shortcode.insert(emoji.shortcode.clone(), self.emojis.len());
+            self.emojis.push(emoji);
+            None
+        };
+        Ok(previous)
+    }
+
+    /// Removes the custom emoji with the given shortcode.
+    pub fn unregister(&mut self, shortcode: &str) -> Option<CustomEmoji> {
+        let i = self.by_shortcode.remove(shortcode)?;
+        let emoji = self.emojis.remove(i);
+        for position in self.by_shortcode.values_mut() {
+            if *position > i {
+                *position -= 1;
+            }
+        }
+        self.index_tokens();
+        Some(emoji)
+    }
+
+    fn index_tokens(&mut self) {
+        let mut tokens = TokenTablesBuilder::default();
+        for (id, emoji) in (0..=u16::MAX).zip(&self.emojis) {
+            tokens.add(id, &emoji.name, &[&emoji.shortcode], &emoji.tags, emoji.category.as_deref().unwrap_or_default());
+        }
+        self.tokens = tokens.build();
+    }
+
+    /// All custom emoji, in registration order.
+    #[must_use]
+    pub fn emojis(&self) -> &[CustomEmoji] {
+        &self.emojis
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.emojis.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.emojis.is_empty()
+    }
+
+    /// Looks up a custom emoji by its shortcode, without colons.
+    #[must_use]
+    pub fn get(&self, shortcode: &str) -> Option<&CustomEmoji> {
+        self.by_shortcode.get(shortcode).map(|&i| &self.emojis[i])
+    }
+
+    /// Searches the custom emoji for `query` by shortcode, name, tags and category, best matches first.
+    ///
+    /// Scores are on the same scale as [`crate::search`], so both results can be merged. An empty query returns every
+    /// custom emoji with a score of `0`.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<Match<&CustomEmoji>> {
+        let query = fold(query.trim());
+        if query.is_empty() {
+            return self.emojis.iter().map(|emoji| Match { emoji, score: 0 }).collect();
+        }
+
+        let mut matches: Vec<_> = search::score(&query, &self.tokens.tables())
+            .into_iter()
+            .map(|(id, mut score)| {
+                let emoji = &self.emojis[usize::from(id)];
+                if search::starts_with_folded(&emoji.name, &query) {
+                    score += search::NAME_PREFIX;
+                }
+                (id, Match { emoji, score })
+            })
+            .collect();
+        matches.sort_unstable_by(|(i, a), (j, b)| b.score.cmp(&a.score).then(i.cmp(j)));
+        matches.into_iter().map(|(_, m)| m).collect()
+    }
+
+    /// Like [`shortcodes::expand`], also resolving custom shortcodes, e.g. `"ship it :partyparrot:"` becomes a
+    /// [`Segment::Text`] followed by a [`Segment::Custom`].
+    #[must_use]
+    pub fn expand<'t>(&self, text: &'t str) -> Vec<Segment<'t, '_>> {
+        let mut segments = Vec::new();
+        let mut last = 0;
+
+        shortcodes::for_each_shortcode(text, |range, code| {
+            let segment = by_shortcode(code).map(Segment::Emoji).or_else(|| self.get(code).map(Segment::Custom));
+            segment.is_some_and(|segment| {
+                if last < range.start {
+                    segments.push(Segment::Text(&text[last..range.start]));
+                }
+                segments.push(segment);
+                last = range.end;
+                true
+            })
+        });
+
+        if last < text.len() {
+            segments.push(Segment::Text(&text[last..]));
+        }
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(shortcode: &str, name: &str, tags: &[&str], category: Option<&str>) -> CustomEmoji {
+        CustomEmoji {
+            shortcode: shortcode.to_owned(),
+            name: name.to_owned(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            image: format!("https://example.com/{shortcode}.gif"),
+            category: category.map(ToOwned::to_owned),
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(custom("partyparrot", "party parrot", &["celebrate", "bird"], Some("Animated"))).unwrap();
+        registry.register(custom("acme", "Acme logo", &[], Some("Company"))).unwrap();
+        registry.register(custom("shipit", "ship it squirrel", &["deploy"], None)).unwrap();
+        registry
+    }
+
+    #[cfg(all(feature = "group-activities", feature = "gemoji-metadata"))]
+    #[test]
+    fn test_register() {
+        let mut registry = registry();
+        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.get("acme").map(|e| e.name.as_str()), Some("Acme logo"));
+
+        let previous = registry.register(custom("acme", "Acme Corp logo", &[], None)).unwrap();
+        assert_eq!(previous.map(|e| e.name), Some("Acme logo".to_owned()));
+        assert_eq!(registry.len(), 3);
+
+        assert_eq!(
+            registry.register(custom("Party Parrot", "", &[], None)),
+            Err(RegisterError::InvalidShortcode("Party Parrot".to_owned()))
+        );
+        assert_eq!(registry.register(custom("tada", "", &[], None)), Err(RegisterError::UnicodeShortcode("tada".to_owned())));
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut registry = registry();
+        let errors = registry.extend([
+            custom("blobcat", "blob cat", &["cat"], None),
+            custom("Blob Cat", "", &[], None),
+            custom("acme", "Acme Corp logo", &[], None),
+        ]);
+        assert_eq!(errors, [RegisterError::InvalidShortcode("Blob Cat".to_owned())]);
+        assert_eq!(registry.len(), 4);
+        assert_eq!(registry.get("acme").map(|e| e.name.as_str()), Some("Acme Corp logo"));
+        assert_eq!(registry.search("blob").first().map(|m| m.emoji.shortcode.as_str()), Some("blobcat"));
+        assert_eq!(registry.search("corp").len(), 1);
+    }
+
+    #[test]
+    fn test_unregister() {
+        let mut registry = registry();
+        assert_eq!(registry.unregister("partyparrot").map(|e| e.name), Some("party parrot".to_owned()));
+        assert_eq!(registry.unregister("partyparrot"), None);
+        assert_eq!(registry.get("shipit").map(|e| e.name.as_str()), Some("ship it squirrel"));
+        assert!(registry.search("parrot").is_empty());
+        assert_eq!(registry.search("squirrel").len(), 1);
+    }
+
+    #[test]
+    fn test_search() {
+        let registry = registry();
+        let top = |query| registry.search(query).first().map(|m| m.emoji.shortcode.as_str());
+        assert_eq!(top("party"), Some("partyparrot"));
+        assert_eq!(top("deploy"), Some("shipit"));
+        assert_eq!(top("company"), Some("acme"));
+        assert_eq!(top("parot"), Some("partyparrot"));
+        assert_eq!(registry.search("").len(), 3);
+        assert!(registry.search("unicorn").is_empty());
+    }
+
+    #[cfg(all(feature = "group-activities", feature = "gemoji-metadata"))]
+    #[test]
+    fn test_expand() {
+        let registry = registry();
+        assert_eq!(
+            registry.expand("ship :shipit: :tada: :nope:"),
+            [
+                Segment::Text("ship "),
+                Segment::Custom(registry.get("shipit").unwrap()),
+                Segment::Text(" "),
+                Segment::Emoji(crate::get("🎉").unwrap()),
+                Segment::Text(" :nope:"),
+            ]
+        );
+        assert_eq!(registry.expand(":acme::acme:").len(), 2);
+        assert_eq!(registry.expand(""), []);
+    }
+}
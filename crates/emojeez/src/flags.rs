@@ -0,0 +1,164 @@
+//! Flag emoji by ISO 3166 code, both regional indicator pairs such as 🇩🇪 for `DE` and tag sequences such as 🏴󠁧󠁢󠁳󠁣󠁴󠁿
+//! for the subdivision `GB-SCT`.
+//!
+//! <https://www.unicode.org/reports/tr51/#Flags>
+
+use unicode_types::{Emoji, SequenceKind};
+
+use crate::{EMOJIS, StaticEmoji, generated::REGIONS, get};
+
+/// 🇦, the regional indicator of `A`.
+const REGIONAL_INDICATOR_A: u32 = 0x1f1e6;
+/// 🏴, which tag characters turn into a subdivision flag.
+const BLACK_FLAG: char = '🏴';
+/// The tag characters U+E0020 to U+E007E mirror ASCII.
+const TAG_OFFSET: u32 = 0xe0000;
+const CANCEL_TAG: char = '\u{e007f}';
+
+/// The flag of a country or region by its ISO 3166-1 alpha-2 code, in any case, e.g. 🇩🇪 for `"DE"`.
+#[must_use]
+pub fn from_iso(code: &str) -> Option<&'static StaticEmoji> {
+    if code.len() != 2 || !code.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+    let flag: Option<String> = code.bytes().map(|b| char::from_u32(REGIONAL_INDICATOR_A + u32::from(b.to_ascii_uppercase() - b'A'))).collect();
+    get(&flag?)
+}
+
+/// The flag of a subdivision by its ISO 3166-2 code, in any case and with or without the hyphen, e.g. 🏴󠁧󠁢󠁳󠁣󠁴󠁿 for
+/// `"gb-sct"`.
+#[must_use]
+pub fn from_subdivision(code: &str) -> Option<&'static StaticEmoji> {
+    let (country, subdivision) = code.split_once('-').or_else(|| code.split_at_checked(2))?;
+    if country.len() != 2 || !(1..=3).contains(&subdivision.len()) || !country.bytes().chain(subdivision.bytes()).all(|b| b.is_ascii_alphanumeric()) {
+        return None;
+    }
+    let tags: Option<String> = country
+        .bytes()
+        .chain(subdivision.bytes())
+        .map(|b| char::from_u32(TAG_OFFSET + u32::from(b.to_ascii_lowercase())))
+        .collect();
+    get(&format!("{BLACK_FLAG}{}{CANCEL_TAG}", tags?))
+}
+
+/// The ISO 3166 code of a flag, e.g. `"DE"` for 🇩🇪 and `"GB-SCT"` for 🏴󠁧󠁢󠁳󠁣󠁴󠁿, or `None` for other emoji such as 🏁.
+#[must_use]
+pub fn to_iso<S, C>(emoji: &Emoji<S, C>) -> Option<String>
+where
+    S: AsRef<str> + Clone,
+    C: AsRef<[S]> + Clone,
+{
+    let flag = emoji.entry.emoji.as_ref();
+    match emoji.entry.sequence_kind {
+        SequenceKind::Flag => flag
+            .chars()
+            .map(|c| u32::from(c).checked_sub(REGIONAL_INDICATOR_A).and_then(|i| char::from_u32(u32::from('A') + i)))
+            .collect(),
+        SequenceKind::Tag => {
+            let tags = flag.strip_prefix(BLACK_FLAG)?.strip_suffix(CANCEL_TAG)?;
+            let code: String = tags
+                .chars()
+                .map(|c| u32::from(c).checked_sub(TAG_OFFSET).and_then(char::from_u32).map(|c| c.to_ascii_uppercase()))
+                .collect::<Option<_>>()?;
+            let (country, subdivision) = code.split_at_checked(2)?;
+            Some(format!("{country}-{subdivision}"))
+        }
+        _ => None,
+    }
+}
+
+/// The RGI flags of a continent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// The English name of the continent, e.g. `"Europe"`, or `"Other"`.
+    pub name: &'static str,
+    pub flags: Vec<&'static StaticEmoji>,
+}
+
+/// All country, territory and subdivision flags of [`EMOJIS`], grouped by continent following the CLDR territory
+/// containment, in [`EMOJIS`] order.
+///
+/// Subdivision flags belong to the continent of their country. Flags outside of any continent, such as 🇪🇺 and 🇺🇳,
+/// come last in a region named `"Other"`.
+#[must_use]
+pub fn regions() -> Vec<Region> {
+    let mut regions: Vec<_> = REGIONS
+        .iter()
+        .map(|&(name, _)| Region { name, flags: Vec::new() })
+        .chain([Region { name: "Other", flags: Vec::new() }])
+        .collect();
+
+    for emoji in EMOJIS {
+        let Some(code) = to_iso(emoji) else {
+            continue;
+        };
+        let country = &code[..2];
+        let i = REGIONS.iter().position(|(_, territories)| territories.binary_search(&country).is_ok()).unwrap_or(REGIONS.len());
+        regions[i].flags.push(emoji);
+    }
+    regions
+}
+
+#[cfg(all(test, feature = "group-flags"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_iso() {
+        assert_eq!(from_iso("DE").map(|e| e.entry.emoji), Some("🇩🇪"));
+        assert_eq!(from_iso("jp").map(|e| e.entry.emoji), Some("🇯🇵"));
+        assert_eq!(from_iso("EU").map(|e| e.entry.emoji), Some("🇪🇺"));
+        assert_eq!(from_iso("ZZ"), None);
+        assert_eq!(from_iso("D"), None);
+        assert_eq!(from_iso("D1"), None);
+    }
+
+    #[test]
+    fn test_from_subdivision() {
+        assert_eq!(from_subdivision("gb-sct").map(|e| e.entry.name), Some("flag: Scotland"));
+        assert_eq!(from_subdivision("GB-ENG").map(|e| e.entry.name), Some("flag: England"));
+        assert_eq!(from_subdivision("gbwls").map(|e| e.entry.name), Some("flag: Wales"));
+        assert_eq!(from_subdivision("us-ca"), None);
+        assert_eq!(from_subdivision("gb-"), None);
+        assert_eq!(from_subdivision("gb-s c"), None);
+        assert_eq!(from_subdivision("aé"), None);
+        assert_eq!(from_subdivision("éab"), None);
+        assert_eq!(from_subdivision("g"), None);
+    }
+
+    #[test]
+    fn test_to_iso() {
+        assert_eq!(get("🇩🇪").and_then(to_iso), Some("DE".to_owned()));
+        assert_eq!(from_subdivision("gb-sct").and_then(to_iso), Some("GB-SCT".to_owned()));
+        assert_eq!(get("🏁").and_then(to_iso), None);
+        assert_eq!(get("😀").and_then(to_iso), None);
+        for emoji in EMOJIS {
+            if let Some(code) = to_iso(emoji) {
+                let flag = if code.len() == 2 { from_iso(&code) } else { from_subdivision(&code) };
+                assert_eq!(flag, Some(emoji), "{code}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_regions() {
+        let regions = regions();
+        let names: Vec<_> = regions.iter().map(|region| region.name).collect();
+        assert_eq!(names, ["Africa", "Americas", "Asia", "Europe", "Oceania", "Other"]);
+
+        let flags = |name| {
+            regions
+                .iter()
+                .find(|region| region.name == name)
+                .map(|region| region.flags.iter().map(|e| e.entry.emoji).collect::<Vec<_>>())
+                .unwrap()
+        };
+        assert!(flags("Europe").contains(&"🇩🇪"));
+        assert!(flags("Europe").contains(&"🏴󠁧󠁢󠁳󠁣󠁴󠁿"));
+        assert!(flags("Asia").contains(&"🇯🇵"));
+        assert_eq!(flags("Other"), ["🇪🇺", "🇺🇳"]);
+
+        let total: usize = regions.iter().map(|region| region.flags.len()).sum();
+        assert_eq!(total, EMOJIS.iter().filter(|e| matches!(e.entry.sequence_kind, SequenceKind::Flag | SequenceKind::Tag)).count());
+    }
+}
@@ -0,0 +1,84 @@
+//! Lookup by id into the feature-trimmed [`EMOJIS`] table.
+//!
+//! The generated indexes and token tables refer to emoji by their id in the complete dataset, whatever features are
+//! enabled. [`emoji`] resolves an id to its entry in [`EMOJIS`], or to `None` when a feature leaves it out.
+
+use phf_shared::HashKey;
+
+use crate::{
+    EMOJIS, StaticEmoji,
+    generated::{DATASET_LEN, EMOJI_INDEX, RUNS},
+};
+
+/// Not compiled in.
+const EXCLUDED: u16 = u16::MAX;
+
+/// The position in [`EMOJIS`] of each id.
+static POSITIONS: [u16; DATASET_LEN] = positions();
+
+const fn positions() -> [u16; DATASET_LEN] {
+    let mut positions = [EXCLUDED; DATASET_LEN];
+    let (mut id, mut position, mut run) = (0, 0u16, 0);
+    while run < RUNS.len() {
+        let (len, included) = RUNS[run];
+        let end = id + len as usize;
+        while id < end {
+            if included {
+                positions[id] = position;
+                position += 1;
+            }
+            id += 1;
+        }
+        run += 1;
+    }
+    positions
+}
+
+/// A perfect-hash index from strings to emoji ids.
+///
+/// The keys themselves are not stored: the candidate id of a key is confirmed against the fields of its entry.
+pub struct Index {
+    pub key: HashKey,
+    pub disps: &'static [(u32, u32)],
+    pub ids: &'static [u16],
+}
+
+impl Index {
+    /// The id `key` refers to, if its emoji is compiled in and `matches` confirms it.
+    pub fn get(&self, key: &str, matches: impl FnOnce(&StaticEmoji) -> bool) -> Option<usize> {
+        if self.ids.is_empty() {
+            return None;
+        }
+        let hashes = phf_shared::hash(key, &self.key);
+        let id = usize::from(self.ids[phf_shared::get_index(&hashes, self.disps, self.ids.len()) as usize]);
+        emoji(id).filter(|&emoji| matches(emoji)).map(|_| id)
+    }
+}
+
+/// The emoji with the given id, unless a feature leaves it out.
+pub fn emoji(id: usize) -> Option<&'static StaticEmoji> {
+    position(id).map(|i| &EMOJIS[i])
+}
+
+/// The position in [`EMOJIS`] of the emoji with the given id, unless a feature leaves it out.
+pub fn position(id: usize) -> Option<usize> {
+    POSITIONS.get(id).filter(|&&i| i != EXCLUDED).map(|&i| usize::from(i))
+}
+
+/// The id of an emoji or of one of its variations.
+pub fn id(emoji: &str) -> Option<usize> {
+    EMOJI_INDEX.get(emoji, |e| e.entry.emoji == emoji || e.variations.contains(&emoji))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_cover_table() {
+        assert_eq!(RUNS.iter().map(|&(len, _)| usize::from(len)).sum::<usize>(), DATASET_LEN);
+        let positions: Vec<_> = (0..DATASET_LEN).filter_map(position).collect();
+        assert_eq!(positions, (0..EMOJIS.len()).collect::<Vec<_>>());
+        assert_eq!(position(DATASET_LEN), None);
+    }
+}
@@ -0,0 +1,76 @@
+//! Localized emoji names and keywords from the CLDR annotations compiled in by `emojeez_generate`.
+//!
+//! Locales are CLDR identifiers such as `"fr"` or `"de_CH"`. A regional locale that was not generated falls back to
+//! its language, so `"fr-CA"` uses `"fr"` when only the latter is available.
+//!
+//! Without the `cldr-annotations` feature no locale is compiled in.
+
+use crate::{
+    StaticEmoji,
+    generated::{ANNOTATION_TOKENS, ANNOTATIONS, Annotations, Tokens},
+    index,
+};
+
+/// The locales with compiled-in annotations.
+pub fn locales() -> impl Iterator<Item = &'static str> {
+    ANNOTATIONS.iter().map(|(locale, _)| *locale)
+}
+
+/// The localized short name of `emoji`, e.g. `"visage rieur"` for 😀 in `"fr"`.
+#[must_use]
+pub fn name(emoji: &StaticEmoji, locale: &str) -> Option<&'static str> {
+    annotation(emoji, locale).map(|(name, _)| name).filter(|name| !name.is_empty())
+}
+
+/// The localized keywords of `emoji`, empty if the locale or the emoji is not annotated.
+#[must_use]
+pub fn keywords(emoji: &StaticEmoji, locale: &str) -> &'static [&'static str] {
+    annotation(emoji, locale).map_or(&[], |(_, keywords)| keywords)
+}
+
+fn annotation(emoji: &StaticEmoji, locale: &str) -> Option<(&'static str, &'static [&'static str])> {
+    let position = index::position(index::id(emoji.entry.emoji)?)?;
+    annotations(locale).map(|annotations| annotations[position])
+}
+
+/// The annotations of `locale`, in [`EMOJIS`](crate::EMOJIS) order.
+pub(crate) fn annotations(locale: &str) -> Option<&'static Annotations> {
+    find(ANNOTATIONS, locale)
+}
+
+/// The search token tables of `locale`, over both names and keywords.
+pub(crate) fn tokens(locale: &str) -> Option<&'static [&'static Tokens]> {
+    find(ANNOTATION_TOKENS, locale)
+}
+
+fn find<T: ?Sized>(tables: &'static [(&'static str, &'static T)], locale: &str) -> Option<&'static T> {
+    let locale = locale.replace('-', "_");
+    let language = locale.split('_').next().unwrap_or_default();
+    [locale.as_str(), language]
+        .into_iter()
+        .find_map(|l| tables.iter().find(|(name, _)| name.eq_ignore_ascii_case(l)))
+        .map(|(_, table)| *table)
+}
+
+#[cfg(all(test, feature = "cldr-annotations"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locales() {
+        assert!(locales().any(|locale| locale == "de"));
+        assert!(locales().any(|locale| locale == "fr"));
+    }
+
+    #[cfg(feature = "group-smileys")]
+    #[test]
+    fn test_name() {
+        let grinning = crate::get("😀").unwrap();
+        assert_eq!(name(grinning, "fr"), Some("visage rieur"));
+        assert_eq!(name(grinning, "fr_CA"), Some("visage rieur"));
+        assert_eq!(name(grinning, "de"), Some("grinsendes Gesicht"));
+        assert_eq!(name(grinning, "xx"), None);
+        assert!(keywords(grinning, "de").contains(&"lustig"));
+        assert!(keywords(grinning, "xx").is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+//! The emoji properties of single code points, from the Unicode emoji-data.txt.
+//!
+//! Unlike [`EMOJIS`](crate::EMOJIS), the property tables are not trimmed by features.
+//!
+//! <https://www.unicode.org/reports/tr51/#Emoji_Properties>
+
+use std::cmp::Ordering;
+
+use crate::generated::{EMOJI, EMOJI_COMPONENT, EMOJI_MODIFIER, EMOJI_MODIFIER_BASE, EMOJI_PRESENTATION, EXTENDED_PICTOGRAPHIC};
+
+/// Whether `c` has the `Emoji` property, e.g. 😀, ☺ or `#`.
+#[must_use]
+pub fn is_emoji(c: char) -> bool {
+    contains(EMOJI, c)
+}
+
+/// Whether `c` is displayed as an emoji by default, without U+FE0F, e.g. 😀 but not ☺.
+#[must_use]
+pub fn is_emoji_presentation(c: char) -> bool {
+    contains(EMOJI_PRESENTATION, c)
+}
+
+/// Whether `c` is a skin tone modifier, 🏻 to 🏿.
+#[must_use]
+pub fn is_emoji_modifier(c: char) -> bool {
+    contains(EMOJI_MODIFIER, c)
+}
+
+/// Whether a skin tone modifier can follow `c`, e.g. 👋.
+#[must_use]
+pub fn is_emoji_modifier_base(c: char) -> bool {
+    contains(EMOJI_MODIFIER_BASE, c)
+}
+
+/// Whether `c` can appear within an emoji sequence without being an emoji on its own there, e.g. `#`, 🏻 or U+200D.
+#[must_use]
+pub fn is_emoji_component(c: char) -> bool {
+    contains(EMOJI_COMPONENT, c)
+}
+
+/// Whether `c` is pictographic, including code points reserved for future emoji, as used for grapheme clusters.
+#[must_use]
+pub fn is_extended_pictographic(c: char) -> bool {
+    contains(EXTENDED_PICTOGRAPHIC, c)
+}
+
+/// Whether `c` is an emoji shown as text by default, which U+FE0F turns into a wide emoji, e.g. ☺ and ☺️.
+#[must_use]
+pub fn needs_variation_selector(c: char) -> bool {
+    is_emoji(c) && !is_emoji_presentation(c)
+}
+
+fn contains(ranges: &[(char, char)], c: char) -> bool {
+    ranges
+        .binary_search_by(|&(first, last)| {
+            if last < c {
+                Ordering::Less
+            } else if first > c {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_properties() {
+        assert!(is_emoji('😀') && is_emoji_presentation('😀') && is_extended_pictographic('😀'));
+        assert!(is_emoji('☺') && !is_emoji_presentation('☺'));
+        assert!(is_emoji('#') && is_emoji_component('#') && !is_extended_pictographic('#'));
+        assert!(is_emoji_modifier('\u{1f3fd}') && !is_emoji_modifier('👋'));
+        assert!(is_emoji_modifier_base('👋') && !is_emoji_modifier_base('😀'));
+        assert!(is_emoji_component('\u{200d}') && !is_emoji('\u{200d}'));
+        assert!(!is_emoji('a') && !is_extended_pictographic('a'));
+    }
+
+    #[test]
+    fn test_needs_variation_selector() {
+        assert!(needs_variation_selector('☺'));
+        assert!(needs_variation_selector('❤'));
+        assert!(!needs_variation_selector('😀'));
+        assert!(!needs_variation_selector('a'));
+    }
+
+    #[test]
+    fn test_ranges_sorted() {
+        for ranges in [EMOJI, EMOJI_PRESENTATION, EMOJI_MODIFIER, EMOJI_MODIFIER_BASE, EMOJI_COMPONENT, EXTENDED_PICTOGRAPHIC] {
+            assert!(ranges.iter().all(|(first, last)| first <= last));
+            assert!(ranges.windows(2).all(|w| w[0].1 < w[1].0));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Finding emoji occurrences in arbitrary text.
+
+use std::{iter::FusedIterator, ops::Range};
+
+use crate::{StaticEmoji, generated::MAX_EMOJI_LEN, get};
+
+/// Returns an iterator over every emoji in `text`, with its byte range.
+///
+/// Matching is greedy: at each position the longest sequence known to [`EMOJIS`](crate::EMOJIS) wins, so ZWJ
+/// sequences, skin-tone modifiers, keycaps, flags and tag sequences are reported as a single emoji rather than as
+/// their components. Minimally-qualified and unqualified forms resolve to their fully-qualified emoji.
+///
+/// ```
+/// # #[cfg(all(feature = "group-people", feature = "group-flags", feature = "skin-tones"))] {
+/// let text = "hi 👋🏽 from 🏳️‍🌈";
+/// let found: Vec<_> = emojeez::scan(text).map(|(range, emoji)| (&text[range], emoji.entry.name)).collect();
+/// assert_eq!(found, [("👋🏽", "waving hand: medium skin tone"), ("🏳️‍🌈", "rainbow flag")]);
+/// # }
+/// ```
+#[must_use]
+pub const fn scan(text: &str) -> Scanner<'_> {
+    Scanner { text, pos: 0 }
+}
+
+/// Iterator returned by [`scan`].
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Iterator for Scanner<'_> {
+    type Item = (Range<usize>, &'static StaticEmoji);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(c) = self.text[self.pos..].chars().next() {
+            let start = self.pos;
+            if may_start_emoji(c) {
+                let longest = (start + 1..=self.text.len().min(start + MAX_EMOJI_LEN))
+                    .rev()
+                    .filter(|&end| self.text.is_char_boundary(end))
+                    .find_map(|end| get(&self.text[start..end]).map(|emoji| (end, emoji)));
+                if let Some((end, emoji)) = longest {
+                    self.pos = end;
+                    return Some((start..end, emoji));
+                }
+            }
+            self.pos += c.len_utf8();
+        }
+        None
+    }
+}
+
+impl FusedIterator for Scanner<'_> {}
+
+/// The only ASCII characters that begin an emoji are the keycap bases.
+const fn may_start_emoji(c: char) -> bool {
+    !c.is_ascii() || c.is_ascii_digit() || matches!(c, '#' | '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(text: &str) -> Vec<&str> {
+        scan(text).map(|(range, _)| &text[range]).collect()
+    }
+
+    #[cfg(all(
+        feature = "group-smileys",
+        feature = "group-people",
+        feature = "group-symbols",
+        feature = "group-flags",
+        feature = "skin-tones",
+        feature = "variations"
+    ))]
+    #[test]
+    fn test_scan_sequences() {
+        assert_eq!(found("👩🏽‍💻 wrote 1️⃣ fix"), ["👩🏽‍💻", "1️⃣"]);
+        assert_eq!(found("🇩🇪🇫🇷"), ["🇩🇪", "🇫🇷"]);
+        assert_eq!(found("🏴󠁧󠁢󠁳󠁣󠁴󠁿!"), ["🏴󠁧󠁢󠁳󠁣󠁴󠁿"]);
+        assert_eq!(found("👨‍👩‍👧‍👦"), ["👨‍👩‍👧‍👦"]);
+        assert_eq!(found("☺ ❤"), ["☺", "❤"]);
+    }
+
+    #[test]
+    fn test_scan_plain_text() {
+        assert_eq!(found("no emoji here, only 123 digits"), Vec::<&str>::new());
+        assert_eq!(found(""), Vec::<&str>::new());
+    }
+
+    #[cfg(feature = "group-people")]
+    #[test]
+    fn test_scan_partial_sequence() {
+        // A dangling ZWJ falls back to the emoji before it.
+        assert_eq!(found("👩\u{200d}x"), ["👩"]);
+    }
+}
@@ -0,0 +1,381 @@
+//! Ranked emoji search over the token index generated alongside [`EMOJIS`].
+//!
+//! Each query word is scored against the emoji's shortcodes, name, tags and subgroup, best match first:
+//!
+//! 1. the emoji itself, e.g. `"👍"`
+//! 2. an exact shortcode, e.g. `"+1"`
+//! 3. a prefix of the whole name or of a shortcode, e.g. `"thu"` for "thumbs up"
+//! 4. a prefix of a word of the name, e.g. `"up"` for "thumbs up"
+//! 5. a tag
+//! 6. a subgroup word, e.g. `"hand"` for "hand-fingers-closed"
+//! 7. a word within one or two typos, e.g. `"thumbz"`
+//!
+//! Every query word must match for an emoji to be returned. [`search_localized`] also matches the CLDR names and
+//! keywords of a [`locale`](crate::locale) as name words.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use unicode_types::{Emoji, SkinTone, fold, fold_chars};
+
+use crate::{
+    EMOJIS, StaticEmoji,
+    generated::{ALIAS_TOKENS, Annotations, NAME_TOKENS, SUBGROUP_TOKENS, TAG_TOKENS, Tokens},
+    get, index, locale,
+};
+
+/// The score of a query that is exactly an emoji.
+pub(crate) const EMOJI: u32 = 1000;
+const EXACT_ALIAS: u32 = 600;
+const ALIAS_PREFIX: u32 = 450;
+/// Added when the name starts with the whole query.
+pub(crate) const NAME_PREFIX: u32 = 100;
+const EXACT_WORD: u32 = 420;
+const WORD_PREFIX: u32 = 400;
+const EXACT_TAG: u32 = 310;
+const TAG_PREFIX: u32 = 300;
+const SUBGROUP: u32 = 200;
+const FUZZY: u32 = 100;
+const TONED: u32 = 5;
+
+/// A search result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<E = &'static StaticEmoji> {
+    pub emoji: E,
+    pub score: u32,
+}
+
+/// Searches [`EMOJIS`] for `query`, best matches first.
+///
+/// Matching ignores case, accents and ligatures, so `"pinata"` finds 🪅 like `"piñata"` does. Results with the same
+/// score keep the Unicode ordering, with default skin tones ahead of their toned variants. An empty query returns every
+/// emoji with a score of `0`.
+#[must_use]
+pub fn search(query: &str) -> Vec<Match> {
+    search_with(query, None)
+}
+
+/// Like [`search`], also matching the names and keywords of `locale`, e.g. `"rieur"` for 😀 in `"fr"`.
+///
+/// Locales without compiled-in annotations behave like [`search`].
+#[must_use]
+pub fn search_localized(query: &str, locale: &str) -> Vec<Match> {
+    search_with(query, locale::tokens(locale).zip(locale::annotations(locale)))
+}
+
+fn search_with(query: &str, localized: Option<(&'static [&'static Tokens], &'static Annotations)>) -> Vec<Match> {
+    let query = fold(query.trim());
+    if query.is_empty() {
+        return EMOJIS.iter().map(|emoji| Match { emoji, score: 0 }).collect();
+    }
+    if let Some(emoji) = get(&query) {
+        return vec![Match { emoji, score: EMOJI }];
+    }
+
+    let tables = TokenTables {
+        aliases: ALIAS_TOKENS,
+        names: NAME_TOKENS,
+        tags: TAG_TOKENS,
+        subgroups: SUBGROUP_TOKENS,
+        localized: localized.map(|(tokens, _)| tokens),
+    };
+    let localized_name = |id| localized.zip(index::position(id)).map_or("", |((_, annotations), i)| annotations[i].0);
+    rank(&query, score(&query, &tables), index::emoji, localized_name)
+}
+
+/// Sorted token tables, each token with the sorted ids of the emoji containing it.
+///
+/// Each kind of token may be split over several tables, as the generated ones are by feature, and an id is scored by
+/// its best match in any of them.
+pub(crate) struct TokenTables<'t, T> {
+    pub aliases: &'t [T],
+    pub names: &'t [T],
+    pub tags: &'t [T],
+    pub subgroups: &'t [T],
+    /// Localized names and keywords, scored like `names`.
+    pub localized: Option<&'t [T]>,
+}
+
+/// Token tables built at runtime, for data that is not generated.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OwnedTokenTables {
+    aliases: Vec<(String, Vec<u16>)>,
+    names: Vec<(String, Vec<u16>)>,
+    tags: Vec<(String, Vec<u16>)>,
+    subgroups: Vec<(String, Vec<u16>)>,
+}
+
+impl OwnedTokenTables {
+    pub const fn tables(&self) -> TokenTables<'_, Vec<(String, Vec<u16>)>> {
+        TokenTables {
+            aliases: std::slice::from_ref(&self.aliases),
+            names: std::slice::from_ref(&self.names),
+            tags: std::slice::from_ref(&self.tags),
+            subgroups: std::slice::from_ref(&self.subgroups),
+            localized: None,
+        }
+    }
+}
+
+/// Collects the tokens of each id into [`OwnedTokenTables`], tokenized like `emojeez_generate` does.
+#[derive(Debug, Default)]
+pub(crate) struct TokenTablesBuilder {
+    aliases: BTreeMap<String, BTreeSet<u16>>,
+    names: BTreeMap<String, BTreeSet<u16>>,
+    tags: BTreeMap<String, BTreeSet<u16>>,
+    subgroups: BTreeMap<String, BTreeSet<u16>>,
+}
+
+impl TokenTablesBuilder {
+    pub fn add<A: AsRef<str>, T: AsRef<str>>(&mut self, id: u16, name: &str, aliases: &[A], tags: &[T], subgroup: &str) {
+        let add = |table: &mut BTreeMap<String, BTreeSet<u16>>, token: String| {
+            table.entry(token).or_default().insert(id);
+        };
+        for alias in aliases {
+            add(&mut self.aliases, fold(alias.as_ref()));
+        }
+        for token in tokenize(name) {
+            add(&mut self.names, token);
+        }
+        for token in tags.iter().flat_map(|tag| tokenize(tag.as_ref())) {
+            add(&mut self.tags, token);
+        }
+        for token in tokenize(subgroup) {
+            add(&mut self.subgroups, token);
+        }
+    }
+
+    pub fn build(self) -> OwnedTokenTables {
+        let table = |tokens: BTreeMap<String, BTreeSet<u16>>| tokens.into_iter().map(|(token, ids)| (token, ids.into_iter().collect())).collect();
+        OwnedTokenTables {
+            aliases: table(self.aliases),
+            names: table(self.names),
+            tags: table(self.tags),
+            subgroups: table(self.subgroups),
+        }
+    }
+}
+
+/// Splits `s` into the [`fold`]ed alphanumeric words of the token tables.
+fn tokenize(s: &str) -> impl Iterator<Item = String> {
+    fold(s)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_owned)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// The score of every emoji id matching all words of the [`fold`]ed `query`.
+pub(crate) fn score<S: AsRef<str>, P: AsRef<[u16]>, T: AsRef<[(S, P)]>>(query: &str, tables: &TokenTables<'_, T>) -> HashMap<u16, u32> {
+    let mut scores: Option<HashMap<u16, u32>> = None;
+    for word in query.split_whitespace() {
+        let word_scores = score_word(word, tables);
+        scores = Some(match scores {
+            None => word_scores,
+            Some(mut scores) => {
+                scores.retain(|id, score| {
+                    word_scores.get(id).is_some_and(|s| {
+                        *score += s;
+                        true
+                    })
+                });
+                scores
+            }
+        });
+    }
+    scores.unwrap_or_default()
+}
+
+/// Turns the `scores` of the [`fold`]ed `query` into matches, best first, resolving ids with `emoji`.
+///
+/// Emoji whose name, or `localized_name`, starts with the whole query rank higher, and toned variants rank just below
+/// their default.
+pub(crate) fn rank<'e, S, C>(query: &str, scores: HashMap<u16, u32>, emoji: impl Fn(usize) -> Option<&'e Emoji<S, C>>, localized_name: impl Fn(usize) -> &'e str) -> Vec<Match<&'e Emoji<S, C>>>
+where
+    S: AsRef<str> + Clone + 'e,
+    C: AsRef<[S]> + Clone + 'e,
+{
+    let mut matches: Vec<_> = scores
+        .into_iter()
+        .filter_map(|(id, mut score)| {
+            let emoji = emoji(usize::from(id))?;
+            if starts_with_folded(emoji.entry.name.as_ref(), query) || starts_with_folded(localized_name(usize::from(id)), query) {
+                score += NAME_PREFIX;
+            }
+            if emoji.skin_tone.is_some_and(|tone| tone != SkinTone::Default) {
+                score -= TONED;
+            }
+            Some((id, Match { emoji, score }))
+        })
+        .collect();
+    matches.sort_unstable_by(|(i, a), (j, b)| b.score.cmp(&a.score).then(i.cmp(j)));
+    matches.into_iter().map(|(_, m)| m).collect()
+}
+
+fn score_word<S: AsRef<str>, P: AsRef<[u16]>, T: AsRef<[(S, P)]>>(word: &str, tables: &TokenTables<'_, T>) -> HashMap<u16, u32> {
+    let mut scores = HashMap::new();
+    let mut add = |postings: &P, score: u32| {
+        for &id in postings.as_ref() {
+            let best = scores.entry(id).or_insert(0);
+            *best = (*best).max(score);
+        }
+    };
+
+    for (token, postings) in tables.aliases.iter().flat_map(|tokens| prefixed(tokens.as_ref(), word)) {
+        add(postings, if token.as_ref() == word { EXACT_ALIAS } else { ALIAS_PREFIX });
+    }
+    for (token, postings) in tables.names.iter().chain(tables.localized.into_iter().flatten()).flat_map(|tokens| prefixed(tokens.as_ref(), word)) {
+        add(postings, if token.as_ref() == word { EXACT_WORD } else { WORD_PREFIX });
+    }
+    for (token, postings) in tables.tags.iter().flat_map(|tokens| prefixed(tokens.as_ref(), word)) {
+        add(postings, if token.as_ref() == word { EXACT_TAG } else { TAG_PREFIX });
+    }
+    for (_, postings) in tables.subgroups.iter().flat_map(|tokens| prefixed(tokens.as_ref(), word)) {
+        add(postings, SUBGROUP);
+    }
+
+    let max_typos = match word.chars().count() {
+        0..=2 => 0,
+        3..=5 => 1,
+        _ => 2,
+    };
+    if max_typos > 0 {
+        for (token, postings) in [tables.aliases, tables.names, tables.tags].into_iter().chain(tables.localized).flatten().flat_map(AsRef::as_ref) {
+            if let Some(typos) = distance(word, token.as_ref(), max_typos).filter(|&d| d > 0) {
+                add(postings, FUZZY - 20 * typos);
+            }
+        }
+    }
+
+    scores
+}
+
+/// The entries of a sorted token table that start with `prefix`.
+fn prefixed<'t, 'a, S: AsRef<str>, P>(tokens: &'t [(S, P)], prefix: &'a str) -> impl Iterator<Item = &'t (S, P)> + 'a
+where
+    't: 'a,
+{
+    let start = tokens.partition_point(|(token, _)| token.as_ref() < prefix);
+    tokens[start..].iter().take_while(move |(token, _)| token.as_ref().starts_with(prefix))
+}
+
+/// Whether `s`, once [`fold`]ed, starts with the folded `prefix`.
+pub(crate) fn starts_with_folded(s: &str, prefix: &str) -> bool {
+    let mut chars = fold_chars(s);
+    prefix.chars().all(|p| chars.next() == Some(p))
+}
+
+/// The optimal string alignment distance between `a` and `b`, if it is at most `max`.
+fn distance(a: &str, b: &str, max: u32) -> Option<u32> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max as usize {
+        return None;
+    }
+
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>(); 3];
+    for i in 1..=a.len() {
+        let (prev2, prev, cur) = (i.wrapping_sub(2) % 3, (i - 1) % 3, i % 3);
+        rows[cur][0] = i;
+        let mut row_min = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (rows[prev][j] + 1).min(rows[cur][j - 1] + 1).min(rows[prev][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(rows[prev2][j - 2] + 1);
+            }
+            rows[cur][j] = d;
+            row_min = row_min.min(d);
+        }
+        if row_min > max as usize {
+            return None;
+        }
+    }
+
+    u32::try_from(rows[a.len() % 3][b.len()]).ok().filter(|&d| d <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(feature = "group-smileys", feature = "group-people", feature = "group-food"))]
+    fn top(query: &str, n: usize) -> Vec<&'static str> {
+        search(query).into_iter().take(n).map(|m| m.emoji.entry.emoji).collect()
+    }
+
+    #[cfg(feature = "group-people")]
+    #[test]
+    fn test_search_prefix() {
+        assert_eq!(top("thu", 1), ["👍"]);
+        assert_eq!(top("Thumbs Up", 1), ["👍"]);
+    }
+
+    #[cfg(all(feature = "group-people", feature = "group-activities", feature = "gemoji-metadata"))]
+    #[test]
+    fn test_search_exact_alias() {
+        assert_eq!(top("+1", 1), ["👍"]);
+        assert_eq!(top("tada", 1), ["🎉"]);
+    }
+
+    #[cfg(feature = "group-smileys")]
+    #[test]
+    fn test_search_all_words() {
+        assert_eq!(top("red heart", 1), ["❤️"]);
+        assert_eq!(top("heart xqzv", 1), Vec::<&str>::new());
+    }
+
+    #[cfg(feature = "group-food")]
+    #[test]
+    fn test_search_typo() {
+        assert!(top("pizzza", 3).contains(&"🍕"));
+        assert_eq!(top("xqzv", 1), Vec::<&str>::new());
+    }
+
+    #[cfg(all(feature = "group-people", feature = "skin-tones"))]
+    #[test]
+    fn test_search_defaults_first() {
+        let results = top("waving hand", 2);
+        assert_eq!(results, ["👋", "👋🏻"]);
+    }
+
+    #[cfg(feature = "group-smileys")]
+    #[test]
+    fn test_search_empty() {
+        assert_eq!(search("").len(), EMOJIS.len());
+        assert_eq!(top("😀", 1), ["😀"]);
+    }
+
+    #[cfg(all(feature = "group-smileys", feature = "group-animals", feature = "cldr-annotations"))]
+    #[test]
+    fn test_search_localized() {
+        assert_eq!(search_localized("grinsendes", "de").first().map(|m| m.emoji.entry.emoji), Some("😀"));
+        assert_eq!(search_localized("hund", "de-AT").first().map(|m| m.emoji.entry.emoji), Some("🐶"));
+        assert!(search("grinsendes").is_empty());
+        assert_eq!(search_localized("thu", "xx"), search("thu"));
+    }
+
+    #[cfg(all(feature = "group-smileys", feature = "cldr-annotations"))]
+    #[test]
+    fn test_search_localized_folded() {
+        let heart = |query| search_localized(query, "fr").iter().any(|m| m.emoji.entry.emoji == "❤️");
+        assert!(heart("cœur"));
+        assert!(heart("coeur"));
+        assert!(heart("CŒUR"));
+    }
+
+    #[cfg(feature = "group-activities")]
+    #[test]
+    fn test_search_folded() {
+        assert_eq!(search("pinata").first().map(|m| m.emoji.entry.emoji), Some("🪅"));
+        assert_eq!(search("piñata").first().map(|m| m.emoji.entry.emoji), Some("🪅"));
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("thumbs", "thumbs", 2), Some(0));
+        assert_eq!(distance("thubms", "thumbs", 2), Some(1));
+        assert_eq!(distance("pizzza", "pizza", 2), Some(1));
+        assert_eq!(distance("cat", "dog", 2), None);
+    }
+}
@@ -0,0 +1,210 @@
+//! Emoji datasets loaded at runtime, e.g. from the JSON output of `emojeez_generate --format json`.
+//!
+//! An [`EmojiSet`] offers the lookups and search of the compile-time [`EMOJIS`](crate::EMOJIS) table over data that
+//! can be replaced without a rebuild.
+
+use std::collections::HashMap;
+
+use unicode_types::{Emoji, fold};
+
+use crate::search::{self, Match, OwnedTokenTables, TokenTablesBuilder};
+
+/// An emoji owning its data, as loaded into an [`EmojiSet`].
+pub type OwnedEmoji = Emoji<String, Vec<String>>;
+
+/// An indexed, owned emoji dataset.
+#[derive(Debug, Clone, Default)]
+pub struct EmojiSet {
+    emojis: Vec<OwnedEmoji>,
+    by_emoji: HashMap<String, usize>,
+    by_shortcode: HashMap<String, usize>,
+    by_name: HashMap<String, usize>,
+    tokens: OwnedTokenTables,
+}
+
+impl EmojiSet {
+    /// Indexes `emojis`, which should be ordered like [`EMOJIS`](crate::EMOJIS): each default skin tone followed by
+    /// its toned variants.
+    ///
+    /// When a string is shared by several emoji, lookups return the first.
+    ///
+    /// # Panics
+    ///
+    /// If there are more than 65 536 emoji.
+    #[must_use]
+    pub fn new(emojis: Vec<OwnedEmoji>) -> Self {
+        assert!(emojis.len() <= usize::from(u16::MAX) + 1, "an EmojiSet holds at most 65 536 emoji");
+
+        let mut by_emoji = HashMap::new();
+        let mut by_shortcode = HashMap::new();
+        let mut by_name = HashMap::new();
+        let mut tokens = TokenTablesBuilder::default();
+
+        // The assertion above leaves an id for every emoji
+        for ((i, emoji), id) in emojis.iter().enumerate().zip(0..=u16::MAX) {
+            let entry = &emoji.entry;
+            for s in std::iter::once(&entry.emoji).chain(&emoji.variations) {
+                by_emoji.entry(s.clone()).or_insert(i);
+            }
+            for alias in &entry.aliases {
+                by_shortcode.entry(alias.clone()).or_insert(i);
+            }
+            by_name.entry(entry.name.clone()).or_insert(i);
+
+            tokens.add(id, &entry.name, &entry.aliases, &entry.tags, &entry.subgroup);
+        }
+
+        Self {
+            emojis,
+            by_emoji,
+            by_shortcode,
+            by_name,
+            tokens: tokens.build(),
+        }
+    }
+
+    /// Loads the JSON array written by `emojeez_generate --format json`.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json).map(Self::new)
+    }
+
+    /// Loads the JSON array written by `emojeez_generate --format json` from a reader, e.g. a file.
+    #[cfg(feature = "serde")]
+    pub fn from_reader(reader: impl std::io::Read) -> Result<Self, serde_json::Error> {
+        serde_json::from_reader(reader).map(Self::new)
+    }
+
+    /// All emoji, in their original order.
+    #[must_use]
+    pub fn emojis(&self) -> &[OwnedEmoji] {
+        &self.emojis
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.emojis.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.emojis.is_empty()
+    }
+
+    /// Like [`crate::get`]: looks up an emoji by its string, including its minimally-qualified and unqualified
+    /// variations.
+    #[must_use]
+    pub fn get(&self, emoji: &str) -> Option<&OwnedEmoji> {
+        self.by_emoji.get(emoji).map(|&i| &self.emojis[i])
+    }
+
+    /// Like [`crate::by_shortcode`]: looks up an emoji by one of its shortcodes, without colons.
+    #[must_use]
+    pub fn by_shortcode(&self, shortcode: &str) -> Option<&OwnedEmoji> {
+        self.by_shortcode.get(shortcode).map(|&i| &self.emojis[i])
+    }
+
+    /// Like [`crate::by_name`]: looks up an emoji by its CLDR short name.
+    #[must_use]
+    pub fn by_name(&self, name: &str) -> Option<&OwnedEmoji> {
+        self.by_name.get(name).map(|&i| &self.emojis[i])
+    }
+
+    /// Like [`crate::search`]: searches the set for `query`, best matches first, with the same ranking.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<Match<&OwnedEmoji>> {
+        let query = fold(query.trim());
+        if query.is_empty() {
+            return self.emojis.iter().map(|emoji| Match { emoji, score: 0 }).collect();
+        }
+        if let Some(emoji) = self.get(&query) {
+            return vec![Match { emoji, score: search::EMOJI }];
+        }
+
+        search::rank(&query, search::score(&query, &self.tokens.tables()), |i| self.emojis.get(i), |_| "")
+    }
+}
+
+impl From<Vec<OwnedEmoji>> for EmojiSet {
+    fn from(emojis: Vec<OwnedEmoji>) -> Self {
+        Self::new(emojis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EMOJIS;
+
+    fn owned_set() -> EmojiSet {
+        EmojiSet::new(
+            EMOJIS
+                .iter()
+                .map(|emoji| Emoji {
+                    entry: unicode_types::Entry {
+                        group: emoji.entry.group,
+                        subgroup: emoji.entry.subgroup.to_owned(),
+                        status: emoji.entry.status,
+                        sequence_kind: emoji.entry.sequence_kind,
+                        unicode_version: emoji.entry.unicode_version,
+                        emoji: emoji.entry.emoji.to_owned(),
+                        name: emoji.entry.name.to_owned(),
+                        ios_version: emoji.entry.ios_version,
+                        tags: emoji.entry.tags.iter().map(ToString::to_string).collect(),
+                        aliases: emoji.entry.aliases.iter().map(ToString::to_string).collect(),
+                    },
+                    skin_tones: emoji.skin_tones,
+                    skin_tone: emoji.skin_tone,
+                    variations: emoji.variations.iter().map(ToString::to_string).collect(),
+                })
+                .collect(),
+        )
+    }
+
+    #[cfg(all(feature = "group-smileys", feature = "group-people", feature = "group-flags", feature = "variations", feature = "gemoji-metadata"))]
+    #[test]
+    fn test_lookups() {
+        let set = owned_set();
+        assert_eq!(set.len(), EMOJIS.len());
+        assert_eq!(set.get("☺").map(|e| e.entry.emoji.as_str()), Some("☺️"));
+        assert_eq!(set.by_shortcode("+1").map(|e| e.entry.emoji.as_str()), Some("👍"));
+        assert_eq!(set.by_name("flag: Germany").map(|e| e.entry.emoji.as_str()), Some("🇩🇪"));
+        assert_eq!(set.get("not an emoji"), None);
+    }
+
+    #[test]
+    fn test_search_matches_static() {
+        let set = owned_set();
+        for query in ["thu", "red heart", "pizzza", "waving hand", "tada", "😀", ""] {
+            let owned: Vec<_> = set.search(query).into_iter().map(|m| (m.emoji.entry.emoji.as_str(), m.score)).collect();
+            let expected: Vec<_> = crate::search(query).into_iter().map(|m| (m.emoji.entry.emoji, m.score)).collect();
+            assert_eq!(owned, expected, "{query}");
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_json() {
+        let json = r#"[{
+            "entry": {
+                "group": "Smileys & Emotion",
+                "subgroup": "face-smiling",
+                "status": "FullyQualified",
+                "sequence_kind": "Basic",
+                "unicode_version": { "major": 1, "minor": 0 },
+                "emoji": "😀",
+                "name": "grinning face",
+                "ios_version": "6.0",
+                "tags": ["smile", "happy"],
+                "aliases": ["grinning"]
+            },
+            "skin_tones": 1,
+            "skin_tone": null,
+            "variations": []
+        }]"#;
+        let set = EmojiSet::from_json(json).unwrap();
+        assert_eq!(set.by_shortcode("grinning").map(|e| e.entry.name.as_str()), Some("grinning face"));
+        assert_eq!(set.search("happy").len(), 1);
+        assert!(EmojiSet::from_json("{}").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! GitHub/Slack style `:shortcode:` conversion, driven by the gemoji aliases of each [`Entry`](unicode_types::Entry).
+
+use std::{borrow::Cow, ops::Range};
+
+use crate::{by_shortcode, scan};
+
+/// Replaces every `:alias:` token that names a known emoji with the emoji itself.
+///
+/// Unknown tokens are left untouched, e.g. `"I :heart: :rust:"` becomes `"I ❤️ :rust:"`.
+#[must_use]
+pub fn expand(text: &str) -> Cow<'_, str> {
+    let mut out = String::new();
+    let mut last = 0;
+
+    for_each_shortcode(text, |range, code| {
+        by_shortcode(code).is_some_and(|emoji| {
+            out.push_str(&text[last..range.start]);
+            out.push_str(emoji.entry.emoji);
+            last = range.end;
+            true
+        })
+    });
+
+    if last == 0 {
+        return Cow::Borrowed(text);
+    }
+    out.push_str(&text[last..]);
+    Cow::Owned(out)
+}
+
+/// Calls `replace` with the range, colons included, and the code of each `:code:` token of `text`, left to right.
+///
+/// `replace` returns whether it used the token; when it does not, the closing colon may open the next token.
+pub(crate) fn for_each_shortcode<'t>(text: &'t str, mut replace: impl FnMut(Range<usize>, &'t str) -> bool) {
+    let mut i = 0;
+    while let Some(start) = text[i..].find(':').map(|p| i + p) {
+        let Some(end) = text[start + 1..].find(':').map(|p| start + 1 + p) else {
+            break;
+        };
+        let code = &text[start + 1..end];
+        i = if is_shortcode(code) && replace(start..end + 1, code) { end + 1 } else { end };
+    }
+}
+
+/// Replaces every emoji that has a shortcode with its primary `:alias:`.
+///
+/// Emoji without a gemoji alias are kept as-is, e.g. `"I ❤️ 🫩"` becomes `"I :heart: 🫩"`.
+#[must_use]
+pub fn collapse(text: &str) -> Cow<'_, str> {
+    let mut out = String::new();
+    let mut last = 0;
+
+    for (range, emoji) in scan(text) {
+        if let Some(alias) = emoji.entry.aliases.first() {
+            out.push_str(&text[last..range.start]);
+            out.push(':');
+            out.push_str(alias);
+            out.push(':');
+            last = range.end;
+        }
+    }
+
+    if last == 0 {
+        return Cow::Borrowed(text);
+    }
+    out.push_str(&text[last..]);
+    Cow::Owned(out)
+}
+
+pub(crate) fn is_shortcode(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'_' | b'+' | b'-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "group-smileys", feature = "group-people", feature = "group-activities", feature = "gemoji-metadata"))]
+    #[test]
+    fn test_expand() {
+        assert_eq!(expand("I :heart: :rust:"), "I ❤️ :rust:");
+        assert_eq!(expand(":+1::tada:"), "👍🎉");
+        assert_eq!(expand("time: 10:30 :smile:"), "time: 10:30 😄");
+        assert!(matches!(expand("no shortcodes: here"), Cow::Borrowed(_)));
+    }
+
+    #[cfg(all(
+        feature = "group-smileys",
+        feature = "group-people",
+        feature = "group-flags",
+        feature = "skin-tones",
+        feature = "variations",
+        feature = "gemoji-metadata"
+    ))]
+    #[test]
+    fn test_collapse() {
+        assert_eq!(collapse("I ❤️ 🫩"), "I :heart: 🫩");
+        assert_eq!(collapse("👍👍🏽"), ":+1:👍🏽");
+        assert_eq!(collapse("🏳️‍🌈"), ":rainbow_flag:");
+        assert_eq!(collapse("☺"), ":relaxed:");
+        assert!(matches!(collapse("plain text"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let text = "ship it :rocket: :+1:";
+        assert_eq!(collapse(&expand(text)), text);
+    }
+}
@@ -0,0 +1,113 @@
+//! Moving between the skin tone variants of an emoji.
+//!
+//! In [`EMOJIS`], every emoji that supports skin tones has [`SkinTone::Default`] and is immediately followed by its
+//! `skin_tones - 1` toned variants, in [`SkinTone`] order.
+
+use std::borrow::Cow;
+
+use unicode_types::SkinTone;
+
+use crate::{EMOJIS, StaticEmoji, index, scan};
+
+/// Skin tone navigation for emoji of the [`EMOJIS`] table.
+pub trait SkinToneExt: Sized {
+    /// The default-tone emoji of a toned emoji, e.g. 👋 for 👋🏿.
+    ///
+    /// Emoji without skin tones are their own base.
+    fn base(self) -> &'static StaticEmoji;
+
+    /// This emoji in the given skin tone, or `None` when it has no such variant.
+    ///
+    /// [`SkinTone::Default`] always resolves to [`base`](Self::base).
+    fn with_skin_tone(self, skin_tone: SkinTone) -> Option<&'static StaticEmoji>;
+
+    /// The default-tone emoji followed by all of its toned variants.
+    fn skin_tone_variants(self) -> &'static [StaticEmoji];
+}
+
+impl SkinToneExt for &'static StaticEmoji {
+    fn base(self) -> &'static StaticEmoji {
+        let Some(i) = index(self) else {
+            return self;
+        };
+        if self.skin_tone.is_none_or(|tone| tone == SkinTone::Default) {
+            return &EMOJIS[i];
+        }
+        EMOJIS[..i].iter().rev().find(|e| e.skin_tone == Some(SkinTone::Default)).unwrap_or(self)
+    }
+
+    fn with_skin_tone(self, skin_tone: SkinTone) -> Option<&'static StaticEmoji> {
+        self.skin_tone_variants().iter().find(|e| e.skin_tone.unwrap_or(SkinTone::Default) == skin_tone)
+    }
+
+    fn skin_tone_variants(self) -> &'static [StaticEmoji] {
+        let base = self.base();
+        index(base).map_or_else(|| std::slice::from_ref(base), |i| &EMOJIS[i..i + base.skin_tones])
+    }
+}
+
+fn index(emoji: &StaticEmoji) -> Option<usize> {
+    index::id(emoji.entry.emoji).and_then(index::position)
+}
+
+/// Replaces every toned emoji in `text` with its default-tone emoji, e.g. `"👋🏿 🧑🏻‍💻"` becomes `"👋 🧑‍💻"`.
+#[must_use]
+pub fn strip_skin_tones(text: &str) -> Cow<'_, str> {
+    let mut out = String::new();
+    let mut last = 0;
+
+    for (range, emoji) in scan(text) {
+        if emoji.skin_tone.is_some_and(|tone| tone != SkinTone::Default) {
+            out.push_str(&text[last..range.start]);
+            out.push_str(emoji.base().entry.emoji);
+            last = range.end;
+        }
+    }
+
+    if last == 0 {
+        return Cow::Borrowed(text);
+    }
+    out.push_str(&text[last..]);
+    Cow::Owned(out)
+}
+
+#[cfg(all(test, feature = "group-smileys", feature = "group-people", feature = "skin-tones"))]
+mod tests {
+    use super::*;
+    use crate::get;
+
+    fn emoji(s: &str) -> &'static StaticEmoji {
+        get(s).unwrap()
+    }
+
+    #[test]
+    fn test_base() {
+        assert_eq!(emoji("👋🏿").base().entry.emoji, "👋");
+        assert_eq!(emoji("👋").base().entry.emoji, "👋");
+        assert_eq!(emoji("😀").base().entry.emoji, "😀");
+        assert_eq!(emoji("🧑🏻‍🐰‍🧑🏼").base().entry.emoji, "👯");
+    }
+
+    #[test]
+    fn test_with_skin_tone() {
+        assert_eq!(emoji("👋").with_skin_tone(SkinTone::MediumDark).map(|e| e.entry.emoji), Some("👋🏾"));
+        assert_eq!(emoji("👋🏻").with_skin_tone(SkinTone::Dark).map(|e| e.entry.emoji), Some("👋🏿"));
+        assert_eq!(emoji("🤝").with_skin_tone(SkinTone::LightAndDark).map(|e| e.entry.emoji), Some("🫱🏻‍🫲🏿"));
+        assert_eq!(emoji("👋🏿").with_skin_tone(SkinTone::Default).map(|e| e.entry.emoji), Some("👋"));
+        assert_eq!(emoji("😀").with_skin_tone(SkinTone::Light), None);
+        assert_eq!(emoji("😀").with_skin_tone(SkinTone::Default).map(|e| e.entry.emoji), Some("😀"));
+    }
+
+    #[test]
+    fn test_skin_tone_variants() {
+        assert_eq!(emoji("👋🏽").skin_tone_variants().len(), 6);
+        assert_eq!(emoji("🧑‍🤝‍🧑").skin_tone_variants().len(), 26);
+        assert_eq!(emoji("😀").skin_tone_variants().len(), 1);
+    }
+
+    #[test]
+    fn test_strip_skin_tones() {
+        assert_eq!(strip_skin_tones("👋🏿 🧑🏻‍💻"), "👋 🧑‍💻");
+        assert!(matches!(strip_skin_tones("👋 😀"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,205 @@
+//! Composing emoji ZWJ sequences from their parts, validated against the RGI sequences of emoji-zwj-sequences.txt.
+//!
+//! [`Emoji::components`](unicode_types::Emoji::components) goes the other way, splitting a sequence into its emoji.
+
+use unicode_types::{SkinTone, ZWJ};
+
+use crate::{generated::ZWJ_SEQUENCES, get};
+
+/// U+FE0F VARIATION SELECTOR-16, requesting the emoji presentation of the character before it.
+const VS16: char = '\u{fe0f}';
+
+/// The gender-neutral person, which a [`Gender`] replaces rather than joins.
+const PERSON: &str = "🧑";
+
+/// Whether `sequence` is an RGI emoji ZWJ sequence, e.g. `"👩‍💻"`, whatever features are enabled.
+#[must_use]
+pub fn is_rgi_zwj_sequence(sequence: &str) -> bool {
+    rgi(sequence).is_some()
+}
+
+fn rgi(sequence: &str) -> Option<&'static str> {
+    ZWJ_SEQUENCES.binary_search(&sequence).ok().map(|i| ZWJ_SEQUENCES[i])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    Female,
+    Male,
+}
+
+impl Gender {
+    /// ♀️ or ♂️, joined to role emoji such as 👮.
+    const fn sign(self) -> &'static str {
+        match self {
+            Self::Female => "♀️",
+            Self::Male => "♂️",
+        }
+    }
+
+    /// 👩 or 👨, in place of the gender-neutral 🧑.
+    const fn person(self) -> &'static str {
+        match self {
+            Self::Female => "👩",
+            Self::Male => "👨",
+        }
+    }
+}
+
+/// Builds a person sequence from its parts, e.g. 🧑 + [`Gender::Female`] + 💻 + [`SkinTone::Medium`] is 👩🏽‍💻.
+///
+/// Parts may be given with or without their U+FE0F variation selector, e.g. `"⚕"` for ⚕️.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Composer<'a> {
+    person: &'a str,
+    gender: Option<Gender>,
+    profession: Option<&'a str>,
+    skin_tone: SkinTone,
+}
+
+impl<'a> Composer<'a> {
+    /// Starts from a person or a role emoji, e.g. 🧑 or 👮.
+    #[must_use]
+    pub const fn new(person: &'a str) -> Self {
+        Self {
+            person,
+            gender: None,
+            profession: None,
+            skin_tone: SkinTone::Default,
+        }
+    }
+
+    /// Turns 🧑 into 👩 or 👨, and joins ♀️ or ♂️ to any other person.
+    #[must_use]
+    pub const fn gender(mut self, gender: Gender) -> Self {
+        self.gender = Some(gender);
+        self
+    }
+
+    /// Joins the object of a profession, e.g. 💻 for a technologist.
+    #[must_use]
+    pub const fn profession(mut self, object: &'a str) -> Self {
+        self.profession = Some(object);
+        self
+    }
+
+    /// Applies a single skin tone modifier to the person. Two-tone values do not compose.
+    #[must_use]
+    pub const fn skin_tone(mut self, skin_tone: SkinTone) -> Self {
+        self.skin_tone = skin_tone;
+        self
+    }
+
+    /// The fully-qualified emoji, if the parts make an RGI ZWJ sequence, or a known emoji when nothing is joined.
+    #[must_use]
+    pub fn compose(&self) -> Option<&'static str> {
+        let modifier = match self.skin_tone {
+            SkinTone::Default => None,
+            tone => Some(modifier(tone)?),
+        };
+
+        let (person, sign) = match self.gender {
+            Some(gender) if self.person == PERSON => (gender.person(), None),
+            gender => (self.person, gender.map(Gender::sign)),
+        };
+        let person = modifier.map_or_else(|| person.to_owned(), |modifier| with_modifier(person, modifier));
+        let joined: Vec<&str> = self.profession.into_iter().chain(sign).collect();
+
+        if joined.is_empty() {
+            return get(&person).map(|emoji| emoji.entry.emoji);
+        }
+
+        // Try every part with and without its variation selector; a toned person never takes one.
+        let parts: Vec<&str> = std::iter::once(person.as_str()).chain(joined).collect();
+        let toggled = usize::from(modifier.is_some());
+        (0..1 << (parts.len() - toggled)).find_map(|mask: usize| {
+            let sequence: Vec<String> = parts
+                .iter()
+                .enumerate()
+                .map(|(i, part)| if i >= toggled && mask & 1 << (i - toggled) != 0 { toggle_vs16(part) } else { (*part).to_owned() })
+                .collect();
+            rgi(&sequence.join(&ZWJ.to_string()))
+        })
+    }
+}
+
+/// The modifier character of a single skin tone.
+const fn modifier(skin_tone: SkinTone) -> Option<char> {
+    match skin_tone {
+        SkinTone::Light => Some('\u{1f3fb}'),
+        SkinTone::MediumLight => Some('\u{1f3fc}'),
+        SkinTone::Medium => Some('\u{1f3fd}'),
+        SkinTone::MediumDark => Some('\u{1f3fe}'),
+        SkinTone::Dark => Some('\u{1f3ff}'),
+        _ => None,
+    }
+}
+
+/// `emoji` with `modifier` after its first character, in place of a variation selector, e.g. 🕵🏽 for 🕵️.
+fn with_modifier(emoji: &str, modifier: char) -> String {
+    let mut chars = emoji.chars();
+    let first = chars.next();
+    let rest = chars.as_str();
+    first.into_iter().chain(std::iter::once(modifier)).chain(rest.strip_prefix(VS16).unwrap_or(rest).chars()).collect()
+}
+
+fn toggle_vs16(part: &str) -> String {
+    part.strip_suffix(VS16).map_or_else(|| format!("{part}{VS16}"), ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(all(feature = "group-people", feature = "skin-tones"))]
+    #[test]
+    fn test_compose() {
+        let person = Composer::new("🧑");
+        assert_eq!(person.gender(Gender::Female).profession("💻").compose(), Some("👩‍💻"));
+        assert_eq!(person.gender(Gender::Female).profession("💻").skin_tone(SkinTone::Medium).compose(), Some("👩🏽‍💻"));
+        assert_eq!(person.profession("⚕").compose(), Some("🧑‍⚕️"));
+        assert_eq!(person.gender(Gender::Male).compose(), Some("👨"));
+        assert_eq!(person.skin_tone(SkinTone::Dark).compose(), Some("🧑🏿"));
+        assert_eq!(Composer::new("👮").gender(Gender::Female).compose(), Some("👮‍♀️"));
+        assert_eq!(Composer::new("🕵").gender(Gender::Male).skin_tone(SkinTone::Light).compose(), Some("🕵🏻‍♂️"));
+        assert_eq!(Composer::new("🕵️").gender(Gender::Male).compose(), Some("🕵️‍♂️"));
+    }
+
+    #[test]
+    fn test_compose_invalid() {
+        assert_eq!(Composer::new("😀").profession("💻").compose(), None);
+        assert_eq!(Composer::new("👩").gender(Gender::Male).compose(), None);
+        assert_eq!(Composer::new("🧑").profession("💻").skin_tone(SkinTone::LightAndDark).compose(), None);
+    }
+
+    #[cfg(all(feature = "group-smileys", feature = "group-people", feature = "group-flags", feature = "skin-tones"))]
+    #[test]
+    fn test_components() {
+        let technologist = get("👩🏽‍💻").unwrap();
+        assert_eq!(technologist.components().collect::<Vec<_>>(), ["👩🏽", "💻"]);
+        assert_eq!(get("🏳️‍🌈").unwrap().components().collect::<Vec<_>>(), ["🏳️", "🌈"]);
+        assert_eq!(get("😀").unwrap().components().collect::<Vec<_>>(), ["😀"]);
+    }
+
+    #[cfg(all(
+        feature = "group-smileys",
+        feature = "group-people",
+        feature = "group-animals",
+        feature = "group-food",
+        feature = "group-travel",
+        feature = "group-activities",
+        feature = "group-objects",
+        feature = "group-symbols",
+        feature = "group-flags",
+        feature = "skin-tones"
+    ))]
+    #[test]
+    fn test_rgi_sequences() {
+        assert!(ZWJ_SEQUENCES.is_sorted());
+        assert!(is_rgi_zwj_sequence("👨‍👩‍👧‍👦"));
+        assert!(!is_rgi_zwj_sequence("😀‍💻"));
+        for sequence in ZWJ_SEQUENCES {
+            assert_eq!(get(sequence).map(|emoji| emoji.entry.emoji), Some(*sequence));
+        }
+    }
+}
@@ -0,0 +1,206 @@
+pub mod regions;
+
+use std::{collections::HashMap, error::Error};
+
+use crate::util::{Release, Source};
+
+/// The CLDR release whose annotations are generated by default.
+pub const VERSION: Release = Release {
+    major: 48,
+    minor: 0,
+    patch: 0,
+};
+
+/// Locales whose CLDR annotations are compiled into `emojeez` by default.
+pub const LOCALES: &[&str] = &["de", "fr"];
+
+/// CLDR tags its releases `release-<major>`, with `-<minor>` only for minor releases, e.g. `release-47-1`.
+fn release_tag(version: Release) -> String {
+    if version.minor == 0 {
+        format!("release-{}", version.major)
+    } else {
+        format!("release-{}-{}", version.major, version.minor)
+    }
+}
+
+fn annotations_url(version: Release, locale: &str) -> String {
+    format!(
+        "https://raw.githubusercontent.com/unicode-org/cldr/{}/common/annotations/{locale}.xml",
+        release_tag(version)
+    )
+}
+
+fn annotations_derived_url(version: Release, locale: &str) -> String {
+    format!(
+        "https://raw.githubusercontent.com/unicode-org/cldr/{}/common/annotationsDerived/{locale}.xml",
+        release_tag(version)
+    )
+}
+
+/// The localized short name and keywords of an emoji.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Annotation {
+    pub name: String,
+    pub keywords: Vec<String>,
+}
+
+/// Annotations of one locale, keyed by emoji without U+FE0F, as CLDR writes them.
+#[derive(Clone, Debug)]
+pub struct Locale {
+    pub locale: String,
+    pub annotations: HashMap<String, Annotation>,
+}
+
+impl Locale {
+    pub fn get(&self, emoji: &str) -> Option<&Annotation> {
+        self.annotations.get(&emoji.replace('\u{fe0f}', ""))
+    }
+}
+
+pub fn build(
+    source: &Source,
+    version: Release,
+    locales: &[String],
+) -> Result<Vec<Locale>, Box<dyn Error>> {
+    locales
+        .iter()
+        .map(|locale| {
+            let mut annotations = HashMap::new();
+            for (url, file_name) in [
+                (
+                    annotations_url(version, locale),
+                    format!("annotations/{locale}.xml"),
+                ),
+                (
+                    annotations_derived_url(version, locale),
+                    format!("annotationsDerived/{locale}.xml"),
+                ),
+            ] {
+                let data = source.read(&url, &file_name)?;
+                parse_annotations(&data, &mut annotations)
+                    .map_err(|e| format!("Failed to parse {url}: {e}"))?;
+            }
+            Ok(Locale {
+                locale: locale.clone(),
+                annotations,
+            })
+        })
+        .collect()
+}
+
+/// Parses the `<annotation>` elements of a CLDR `annotations` or `annotationsDerived` file.
+///
+/// ```xml
+/// <annotation cp="😀">face | grin | grinning face</annotation>
+/// <annotation cp="😀" type="tts">grinning face</annotation>
+/// ```
+pub fn parse_annotations(
+    data: &str,
+    annotations: &mut HashMap<String, Annotation>,
+) -> Result<(), Box<dyn Error>> {
+    for line in data.lines() {
+        let Some(rest) = line.trim().strip_prefix("<annotation cp=\"") else {
+            continue;
+        };
+        let (cp, rest) = rest.split_once('"').ok_or("expected closing quote")?;
+        let (attrs, rest) = rest.split_once('>').ok_or("expected '>'")?;
+        let text = rest
+            .strip_suffix("</annotation>")
+            .ok_or("expected </annotation>")?;
+
+        // "↑↑↑" marks values inherited from the parent locale.
+        if text == "↑↑↑" {
+            continue;
+        }
+
+        let annotation = annotations
+            .entry(unescape(cp).replace('\u{fe0f}', ""))
+            .or_default();
+        if attrs.contains("type=\"tts\"") {
+            annotation.name = unescape(text);
+        } else {
+            annotation.keywords = text.split(" | ").map(unescape).collect();
+        }
+    }
+    Ok(())
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    const FIXTURE: &str = r#"<?xml version="1.0" encoding="UTF-8" ?>
+<ldml>
+	<identity>
+		<language type="fr"/>
+	</identity>
+	<annotations>
+		<annotation cp="😀">content | grand sourire | visage</annotation>
+		<annotation cp="😀" type="tts">visage rieur</annotation>
+		<annotation cp="☺">sourire | visage</annotation>
+		<annotation cp="☺" type="tts">visage souriant</annotation>
+		<annotation cp="&amp;">esperluette | et</annotation>
+		<annotation cp="🫠" type="tts">↑↑↑</annotation>
+	</annotations>
+</ldml>
+"#;
+
+    #[test]
+    fn test_parse_annotations() {
+        let mut annotations = HashMap::new();
+        parse_annotations(FIXTURE, &mut annotations).unwrap();
+        let locale = Locale {
+            locale: "fr".to_owned(),
+            annotations,
+        };
+
+        assert_eq!(
+            locale.get("😀"),
+            Some(&Annotation {
+                name: "visage rieur".to_owned(),
+                keywords: vec![
+                    "content".to_owned(),
+                    "grand sourire".to_owned(),
+                    "visage".to_owned()
+                ],
+            })
+        );
+        assert_eq!(
+            locale.get("☺️").map(|a| a.name.as_str()),
+            Some("visage souriant")
+        );
+        assert_eq!(locale.get("&").map(|a| a.keywords.len()), Some(2));
+        assert_eq!(locale.get("🫠"), None);
+    }
+
+    #[test]
+    fn test_build_from_data_dir() {
+        let source = Source::DataDir(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures"));
+        let locales = build(&source, VERSION, &["de".to_owned(), "fr".to_owned()]).unwrap();
+        let name = |locale: &Locale, emoji| locale.get(emoji).map(|a| a.name.clone());
+
+        assert_eq!(locales.len(), 2);
+        assert_eq!(
+            name(&locales[0], "😀").as_deref(),
+            Some("grinsendes Gesicht")
+        );
+        assert_eq!(name(&locales[1], "😀").as_deref(), Some("visage rieur"));
+        assert_eq!(name(&locales[1], "❤️").as_deref(), Some("cœur rouge"));
+        // Skin tone variants come from annotationsDerived
+        assert_eq!(
+            name(&locales[0], "👍🏻").as_deref(),
+            Some("Daumen hoch: helle Hautfarbe")
+        );
+        assert!(build(&source, VERSION, &["xx".to_owned()]).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use std::{collections::HashMap, error::Error};
+
+use crate::util::{Release, Source};
+
+use super::release_tag;
+
+/// The UN M.49 continents that flags are grouped by, with their English names.
+const CONTINENTS: [(&str, &str); 5] = [
+    ("002", "Africa"),
+    ("019", "Americas"),
+    ("142", "Asia"),
+    ("150", "Europe"),
+    ("009", "Oceania"),
+];
+
+/// A continent and the sorted ISO 3166 codes of its countries and territories.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub name: &'static str,
+    pub territories: Vec<String>,
+}
+
+fn supplemental_url(version: Release) -> String {
+    format!(
+        "https://raw.githubusercontent.com/unicode-org/cldr/{}/common/supplemental/supplementalData.xml",
+        release_tag(version)
+    )
+}
+
+pub fn build(source: &Source, version: Release) -> Result<Vec<Region>, Box<dyn Error>> {
+    let url = supplemental_url(version);
+    let data = source.read(&url, "supplemental/supplementalData.xml")?;
+    let groups = parse_containment(&data).map_err(|e| format!("Failed to parse {url}: {e}"))?;
+
+    CONTINENTS
+        .iter()
+        .map(|&(code, name)| {
+            let mut territories = Vec::new();
+            expand(&groups, code, 0, &mut territories)?;
+            territories.sort_unstable();
+            territories.dedup();
+            Ok(Region { name, territories })
+        })
+        .collect()
+}
+
+/// Parses the `<group>` elements of the territory containment, skipping the alternative `grouping` and `deprecated`
+/// ones.
+///
+/// ```xml
+/// <group type="155" contains="AT BE CH DE FR LI LU MC NL"/>
+/// <group type="019" contains="003 419" status="grouping"/>
+/// ```
+fn parse_containment(data: &str) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let mut groups = HashMap::new();
+    for line in data.lines() {
+        let Some(rest) = line.trim().strip_prefix("<group type=\"") else {
+            continue;
+        };
+        let (code, rest) = rest.split_once('"').ok_or("expected closing quote")?;
+        if rest.contains("status=") {
+            continue;
+        }
+        let (contains, _) = rest
+            .trim_start()
+            .strip_prefix("contains=\"")
+            .and_then(|rest| rest.split_once('"'))
+            .ok_or("expected contains")?;
+        groups.insert(
+            code.to_owned(),
+            contains.split_whitespace().map(ToOwned::to_owned).collect(),
+        );
+    }
+    Ok(groups)
+}
+
+/// Adds the territories of `code` to `territories`, going through its subregions.
+fn expand(
+    groups: &HashMap<String, Vec<String>>,
+    code: &str,
+    depth: usize,
+    territories: &mut Vec<String>,
+) -> Result<(), String> {
+    if depth > 8 {
+        return Err(format!("territory containment too deep at {code}"));
+    }
+    match groups.get(code) {
+        Some(children) => {
+            for child in children {
+                expand(groups, child, depth + 1, territories)?;
+            }
+        }
+        None if depth == 0 => return Err(format!("missing region {code}")),
+        None => territories.push(code.to_owned()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::cldr::VERSION;
+
+    #[test]
+    fn test_build_from_data_dir() {
+        let source = Source::DataDir(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures"));
+        let regions = build(&source, VERSION).unwrap();
+        let territories = |name: &str| {
+            regions
+                .iter()
+                .find(|region| region.name == name)
+                .map(|region| region.territories.clone())
+                .unwrap()
+        };
+
+        assert_eq!(regions.len(), 5);
+        assert_eq!(territories("Europe"), ["DE", "FR", "GB"]);
+        assert_eq!(territories("Americas"), ["CA", "US"]);
+        assert_eq!(territories("Oceania"), ["AQ", "AU", "NZ"]);
+    }
+
+    #[test]
+    fn test_parse_containment() {
+        let groups = parse_containment(
+            r#"<group type="019" contains="021"/>
+            <group type="019" contains="003 419" status="grouping"/>"#,
+        )
+        .unwrap();
+        assert_eq!(groups["019"], ["021"]);
+        assert!(parse_containment(r#"<group type="019"/>"#).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use std::path::PathBuf;
+
+use crate::{
+    cldr, github, unicode,
+    util::{Release, Source},
+};
+
+pub const USAGE: &str = "\
+Usage: emojeez_generate [OPTIONS]
+
+Generates the emojeez emoji table from the Unicode emoji data, gemoji and the CLDR annotations.
+
+Options:
+      --unicode <VERSION>  Unicode emoji version [default: 17.0.0]
+      --gemoji <VERSION>   gemoji version [default: 4.1.0]
+      --cldr <VERSION>     CLDR release of the annotations [default: 48]
+      --locales <LOCALES>  Comma-separated CLDR locales to include, e.g. `de,fr` [default: de,fr]
+  -o, --output <PATH>      Output file, required for formats other than rust [default: crates/emojeez/src/generated.rs]
+      --format <FORMAT>    Output format: rust, json, ndjson, csv or msgpack [default: rust]
+      --dry-run            Generate without writing, exiting with 1 when the output is out of date
+      --diff               Like --dry-run, also printing a diff against the current output
+      --compare-unicode <VERSION>
+                           Instead of generating, report the changes from this Unicode emoji version
+      --compare-gemoji <VERSION>
+                           gemoji version to compare with [default: --gemoji]
+      --report-format <FORMAT>
+                           Format of the report: text or json [default: text]
+      --data-dir <PATH>    Read the input files from a directory instead of downloading them [env: EMOJEEZ_DATA_DIR]
+      --refresh            Download the input files again instead of using the cache
+      --allow-unpinned     Use downloaded files without a pinned SHA-256 in sources.sha256, printing their digest
+  -h, --help               Print this help
+";
+
+/// Environment variable naming a data directory, for when `--data-dir` is not given.
+pub const DATA_DIR_ENV: &str = "EMOJEEZ_DATA_DIR";
+
+/// The generated file format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The `emojeez` table.
+    Rust,
+    /// A pretty-printed JSON array.
+    Json,
+    /// Newline-delimited JSON, one emoji per line.
+    Ndjson,
+    Csv,
+    MessagePack,
+}
+
+impl Format {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "rust" => Ok(Self::Rust),
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            "msgpack" => Ok(Self::MessagePack),
+            _ => Err(format!(
+                "unknown format '{s}', expected rust, json, ndjson, csv or msgpack"
+            )),
+        }
+    }
+}
+
+/// What to do with the generated output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Write,
+    DryRun,
+    Diff,
+}
+
+/// A dataset to report the changes from, instead of generating.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Compare {
+    pub unicode: Release,
+    pub gemoji: Release,
+    pub format: ReportFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+impl ReportFormat {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown report format '{s}', expected text or json"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Args {
+    pub source: Source,
+    pub unicode: Release,
+    pub gemoji: Release,
+    pub cldr: Release,
+    pub locales: Vec<String>,
+    pub output: PathBuf,
+    pub format: Format,
+    pub mode: Mode,
+    pub compare: Option<Compare>,
+    pub help: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            source: Source::Download {
+                refresh: false,
+                allow_unpinned: false,
+            },
+            unicode: unicode::VERSION,
+            gemoji: github::VERSION,
+            cldr: cldr::VERSION,
+            locales: cldr::LOCALES.iter().map(ToString::to_string).collect(),
+            output: PathBuf::from("crates/emojeez/src/generated.rs"),
+            format: Format::Rust,
+            mode: Mode::Write,
+            compare: None,
+            help: false,
+        }
+    }
+}
+
+impl Args {
+    /// Parses the command-line arguments, without the program name. `env_data_dir` is the value of [`DATA_DIR_ENV`].
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        env_data_dir: Option<String>,
+    ) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut output = None;
+        let mut compare_unicode = None;
+        let mut compare_gemoji = None;
+        let mut report_format = ReportFormat::Text;
+        let mut data_dir = None;
+        let mut refresh = false;
+        let mut allow_unpinned = false;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_owned(), Some(value.to_owned()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{flag} expects a value"))
+            };
+
+            match flag.as_str() {
+                "--unicode" => parsed.unicode = value()?.parse()?,
+                "--gemoji" => parsed.gemoji = value()?.parse()?,
+                "--cldr" => parsed.cldr = value()?.parse()?,
+                "--locales" => {
+                    parsed.locales = value()?
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|locale| !locale.is_empty())
+                        .map(ToOwned::to_owned)
+                        .collect();
+                }
+                "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+                "--format" => parsed.format = Format::parse(&value()?)?,
+                "--dry-run" => parsed.mode = Mode::DryRun,
+                "--diff" => parsed.mode = Mode::Diff,
+                "--compare-unicode" => compare_unicode = Some(value()?.parse()?),
+                "--compare-gemoji" => compare_gemoji = Some(value()?.parse()?),
+                "--report-format" => report_format = ReportFormat::parse(&value()?)?,
+                "--data-dir" => data_dir = Some(value()?),
+                "--refresh" => refresh = true,
+                "--allow-unpinned" => allow_unpinned = true,
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(format!("unexpected argument: {flag}")),
+            }
+        }
+
+        parsed.compare = match (compare_unicode, compare_gemoji) {
+            (Some(unicode), gemoji) => Some(Compare {
+                unicode,
+                gemoji: gemoji.unwrap_or(parsed.gemoji),
+                format: report_format,
+            }),
+            (None, Some(_)) => return Err("--compare-gemoji requires --compare-unicode".to_owned()),
+            (None, None) => None,
+        };
+
+        match output {
+            Some(output) => parsed.output = output,
+            None if parsed.format != Format::Rust && parsed.compare.is_none() => {
+                return Err("--output is required for formats other than rust".to_owned());
+            }
+            None => {}
+        }
+
+        parsed.source = match data_dir.or_else(|| env_data_dir.filter(|dir| !dir.is_empty())) {
+            Some(_) if refresh => {
+                return Err(
+                    "--refresh downloads, it cannot be used with a data directory".to_owned(),
+                );
+            }
+            Some(_) if allow_unpinned => {
+                return Err(
+                    "--allow-unpinned applies to downloads, a data directory is verified against its own sources.sha256"
+                        .to_owned(),
+                );
+            }
+            Some(dir) => Source::DataDir(PathBuf::from(dir)),
+            None => Source::Download {
+                refresh,
+                allow_unpinned,
+            },
+        };
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(ToString::to_string), None)
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        assert_eq!(parse(&[]), Ok(Args::default()));
+    }
+
+    #[test]
+    fn test_parse_versions_and_output() {
+        let args = parse(&[
+            "--unicode",
+            "15.1",
+            "--gemoji=4.0.0",
+            "--locales",
+            " fr,",
+            "-o",
+            "legacy.rs",
+            "--dry-run",
+        ])
+        .unwrap();
+        assert_eq!(args.unicode, "15.1.0".parse().unwrap());
+        assert_eq!(args.gemoji, "4.0.0".parse().unwrap());
+        assert_eq!(args.locales, ["fr"]);
+        assert_eq!(args.output, PathBuf::from("legacy.rs"));
+        assert_eq!(args.mode, Mode::DryRun);
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let args = parse(&["--format", "msgpack", "-o", "emojis.msgpack"]).unwrap();
+        assert_eq!(args.format, Format::MessagePack);
+        assert_eq!(args.output, PathBuf::from("emojis.msgpack"));
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let args = parse(&["--compare-unicode", "16.0", "--report-format", "json"]).unwrap();
+        assert_eq!(
+            args.compare,
+            Some(Compare {
+                unicode: "16.0.0".parse().unwrap(),
+                gemoji: github::VERSION,
+                format: ReportFormat::Json,
+            })
+        );
+        assert!(parse(&["--compare-gemoji", "4.0.0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            parse(&["--data-dir", "fixtures"]).map(|args| args.source),
+            Ok(Source::DataDir(PathBuf::from("fixtures")))
+        );
+        assert_eq!(
+            parse(&["--refresh"]).map(|args| args.source),
+            Ok(Source::Download {
+                refresh: true,
+                allow_unpinned: false
+            })
+        );
+        assert_eq!(
+            parse(&["--allow-unpinned"]).map(|args| args.source),
+            Ok(Source::Download {
+                refresh: false,
+                allow_unpinned: true
+            })
+        );
+        assert_eq!(
+            Args::parse(Vec::new(), Some("fixtures".to_owned())).map(|args| args.source),
+            Ok(Source::DataDir(PathBuf::from("fixtures")))
+        );
+        assert!(parse(&["--refresh", "--data-dir", "fixtures"]).is_err());
+        assert!(parse(&["--allow-unpinned", "--data-dir", "fixtures"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["--data-dir"]).is_err());
+        assert!(parse(&["--unicode", "latest"]).is_err());
+        assert!(parse(&["--format", "yaml"]).is_err());
+        assert!(parse(&["--format", "json"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! Data formats for consumers outside of Rust, with the same merged Unicode and gemoji data as the `emojeez` table.
+
+use unicode_types::{Emoji, Version};
+
+type OwnedEmoji = Emoji<String, Vec<String>>;
+
+/// A pretty-printed JSON array.
+pub fn json(data: &[OwnedEmoji]) -> Result<Vec<u8>, serde_json::Error> {
+    let mut out = serde_json::to_vec_pretty(data)?;
+    out.push(b'\n');
+    Ok(out)
+}
+
+/// One JSON object per line.
+pub fn ndjson(data: &[OwnedEmoji]) -> Result<Vec<u8>, serde_json::Error> {
+    let mut out = Vec::new();
+    for emoji in data {
+        serde_json::to_writer(&mut out, emoji)?;
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+/// CSV with a header row, one emoji per row. List columns are joined with `|`, versions are written as `major.minor`.
+pub fn csv(data: &[OwnedEmoji]) -> Vec<u8> {
+    const HEADER: [&str; 13] = [
+        "emoji",
+        "name",
+        "group",
+        "subgroup",
+        "status",
+        "sequence_kind",
+        "unicode_version",
+        "ios_version",
+        "tags",
+        "aliases",
+        "skin_tones",
+        "skin_tone",
+        "variations",
+    ];
+
+    let version = |v: Version| format!("{}.{}", v.major, v.minor);
+
+    let mut out = String::new();
+    write_csv_row(&mut out, &HEADER.map(ToOwned::to_owned));
+    for emoji in data {
+        let entry = &emoji.entry;
+        write_csv_row(
+            &mut out,
+            &[
+                entry.emoji.clone(),
+                entry.name.clone(),
+                entry.group.as_str().to_owned(),
+                entry.subgroup.clone(),
+                format!("{:?}", entry.status),
+                format!("{:?}", entry.sequence_kind),
+                version(entry.unicode_version),
+                entry.ios_version.map(version).unwrap_or_default(),
+                entry.tags.join("|"),
+                entry.aliases.join("|"),
+                emoji.skin_tones.to_string(),
+                emoji
+                    .skin_tone
+                    .map(|tone| format!("{tone:?}"))
+                    .unwrap_or_default(),
+                emoji.variations.join("|"),
+            ],
+        );
+    }
+    out.into_bytes()
+}
+
+fn write_csv_row(out: &mut String, fields: &[String]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// An array of maps in the msgpack binary format, with the same fields as [`json`].
+pub fn msgpack(data: &[OwnedEmoji]) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    rmp_serde::to_vec_named(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use unicode_types::{Entry, Group, SequenceKind, SkinTone, Status};
+
+    use super::*;
+
+    fn waving_hand() -> OwnedEmoji {
+        Emoji {
+            entry: Entry {
+                group: Group::PeopleAndBody,
+                subgroup: "hand-fingers-open".to_owned(),
+                status: Status::FullyQualified,
+                sequence_kind: SequenceKind::Basic,
+                unicode_version: Version { major: 0, minor: 6 },
+                emoji: "👋".to_owned(),
+                name: "waving hand".to_owned(),
+                ios_version: Some(Version { major: 6, minor: 0 }),
+                tags: vec!["goodbye".to_owned()],
+                aliases: vec!["wave".to_owned()],
+            },
+            skin_tones: 6,
+            skin_tone: Some(SkinTone::Default),
+            variations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let out = String::from_utf8(ndjson(&[waving_hand(), waving_hand()]).unwrap()).unwrap();
+        assert_eq!(out.lines().count(), 2);
+        let value: Value = serde_json::from_str(out.lines().next().unwrap()).unwrap();
+        assert_eq!(value["entry"]["group"], "People & Body");
+        assert_eq!(value["skin_tone"], "Default");
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut emoji = waving_hand();
+        emoji.entry.name = "waving \"hello\", hand".to_owned();
+        let out = String::from_utf8(csv(&[emoji])).unwrap();
+        let mut lines = out.lines();
+        assert!(lines.next().unwrap().starts_with("emoji,name,group,"));
+        assert_eq!(
+            lines.next(),
+            Some(
+                "👋,\"waving \"\"hello\"\", hand\",People & Body,hand-fingers-open,FullyQualified,Basic,0.6,6.0,goodbye,wave,6,Default,"
+            )
+        );
+    }
+
+    #[test]
+    fn test_msgpack() {
+        let mut toned = waving_hand();
+        toned.entry.emoji = "👋🏽".to_owned();
+        toned.entry.ios_version = None;
+        toned.skin_tone = Some(SkinTone::Medium);
+        let data = [waving_hand(), toned];
+
+        let out = msgpack(&data).unwrap();
+        assert_eq!(out[0], 0x92);
+        assert_eq!(
+            rmp_serde::from_slice::<Vec<OwnedEmoji>>(&out).unwrap(),
+            data
+        );
+    }
+}
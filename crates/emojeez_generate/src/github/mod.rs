@@ -3,16 +3,17 @@ use std::error::Error;
 use serde::Deserialize;
 use unicode_types::Version;
 
-use crate::util;
+use crate::util::{Release, Source};
 
-pub const VERSION_MAJOR: usize = 4;
-pub const VERSION_MINOR: usize = 1;
-pub const VERSION_PATCH: usize = 0;
+/// The gemoji version generated by default.
+pub const VERSION: Release = Release {
+    major: 4,
+    minor: 1,
+    patch: 0,
+};
 
-fn genmoji_url() -> String {
-    format!(
-        "https://raw.githubusercontent.com/github/gemoji/v{VERSION_MAJOR}.{VERSION_MINOR}.{VERSION_PATCH}/db/emoji.json"
-    )
+fn genmoji_url(version: Release) -> String {
+    format!("https://raw.githubusercontent.com/github/gemoji/v{version}/db/emoji.json")
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -23,8 +24,8 @@ pub struct Gemoji {
     pub tags: Vec<String>,
 }
 
-pub fn build() -> Result<Vec<Gemoji>, Box<dyn Error>> {
-    let buf = util::cached_download(&genmoji_url())?;
+pub fn build(source: &Source, version: Release) -> Result<Vec<Gemoji>, Box<dyn Error>> {
+    let buf = source.read(&genmoji_url(version), "emoji.json")?;
     let emojis: Vec<Gemoji> = serde_json::from_str(&buf)?;
     Ok(emojis)
 }
@@ -1,15 +1,91 @@
-use std::{fs, path::PathBuf};
+use std::{env, error::Error, fs, process};
 
+mod cldr;
+mod cli;
+mod export;
 mod github;
+mod manifest;
 mod output;
+mod report;
 mod unicode;
 mod util;
 
+use cli::{Args, Format, Mode, ReportFormat};
+
 fn main() {
-    let unicode_data = unicode::build().unwrap();
+    let args =
+        Args::parse(env::args().skip(1), env::var(cli::DATA_DIR_ENV).ok()).unwrap_or_else(|e| {
+            eprintln!("error: {e}\n\n{}", cli::USAGE);
+            process::exit(2);
+        });
+    if args.help {
+        print!("{}", cli::USAGE);
+        return;
+    }
+
+    if let Err(e) = run(&args) {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let gemojis = github::build(&args.source, args.gemoji)?;
+
+    let unicode_data = unicode::build(&args.source, args.unicode, &gemojis)?;
+
+    if let Some(compare) = &args.compare {
+        let old_gemojis = github::build(&args.source, compare.gemoji)?;
+        let old_data = unicode::build(&args.source, compare.unicode, &old_gemojis)?;
+        let report = report::Report::new(
+            format!("Unicode {} / gemoji {}", compare.unicode, compare.gemoji),
+            &old_data,
+            format!("Unicode {} / gemoji {}", args.unicode, args.gemoji),
+            &unicode_data,
+        );
+        match compare.format {
+            ReportFormat::Text => print!("{report}"),
+            ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        }
+        return Ok(());
+    }
+
+    let locales = cldr::build(&args.source, args.cldr, &args.locales)?;
+
+    let generated = match args.format {
+        Format::Rust => {
+            let properties = unicode::properties::build(&args.source, args.unicode)?;
+            let regions = cldr::regions::build(&args.source, args.cldr)?;
+            output::generate_rust_code(&unicode_data, &properties, &regions, &locales).into_bytes()
+        }
+        Format::Json => export::json(&unicode_data)?,
+        Format::Ndjson => export::ndjson(&unicode_data)?,
+        Format::Csv => export::csv(&unicode_data),
+        Format::MessagePack => export::msgpack(&unicode_data)?,
+    };
+
+    let tmp = util::temp_path(&args.output);
+    fs::write(&tmp, generated)?;
+    if args.format == Format::Rust {
+        util::rustfmt(&tmp)?;
+    }
+
+    if args.mode == Mode::Write {
+        fs::rename(&tmp, &args.output)?;
+        return Ok(());
+    }
 
-    let generated_code = output::generate_rust_code(&unicode_data);
+    let up_to_date = fs::read(&args.output).ok() == Some(fs::read(&tmp)?);
+    if args.mode == Mode::Diff && !up_to_date {
+        util::diff(&args.output, &tmp)?;
+    }
+    fs::remove_file(&tmp)?;
 
-    let out_dir = PathBuf::from("crates/emojeez/src/lib.rs");
-    fs::write(out_dir, generated_code).unwrap();
+    if up_to_date {
+        eprintln!("{} is up to date", args.output.display());
+    } else {
+        eprintln!("{} is out of date", args.output.display());
+        process::exit(1);
+    }
+    Ok(())
 }
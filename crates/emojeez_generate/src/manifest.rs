@@ -0,0 +1,116 @@
+//! Pinned SHA-256 digests of the generator's inputs, from `sources.sha256`.
+
+use std::{fs, io, path::Path};
+
+use sha2::{Digest, Sha256};
+
+const MANIFEST: &str = include_str!("../sources.sha256");
+
+/// The manifest a data directory may hold, in `sha256sum` format, i.e. one `<digest>  <file name>` per line.
+pub const DATA_DIR_MANIFEST: &str = "sources.sha256";
+
+/// Checks `data`, downloaded from `url`, against its pinned digest.
+///
+/// Sources without a pinned digest are an error, unless `allow_unpinned` is set to use them with a warning, so that a
+/// new version can be checked against upstream and pinned after a first download.
+pub fn verify(url: &str, data: &str, allow_unpinned: bool) -> Result<(), io::Error> {
+    verify_with(MANIFEST, url, data, allow_unpinned)
+}
+
+/// Checks `data`, read from `file_name` in the data directory `dir`, against the [`DATA_DIR_MANIFEST`] of `dir`.
+///
+/// Data directories hold their own files, such as trimmed test fixtures, which the pins of the downloads do not
+/// apply to. Directories without a manifest are not verified, but with one every file must be listed.
+pub fn verify_data_dir(dir: &Path, file_name: &str, data: &str) -> Result<(), io::Error> {
+    match fs::read_to_string(dir.join(DATA_DIR_MANIFEST)) {
+        Ok(manifest) => verify_with(&manifest, file_name, data, false),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+fn verify_with(
+    manifest: &str,
+    url: &str,
+    data: &str,
+    allow_unpinned: bool,
+) -> Result<(), io::Error> {
+    let digest = hex::encode(Sha256::digest(data.as_bytes()));
+    match expected(manifest, url) {
+        Some(expected) if expected.eq_ignore_ascii_case(&digest) => Ok(()),
+        Some(expected) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "SHA-256 mismatch for {url}\n    expected {expected}\n    got      {digest}\nthe file may be truncated or tampered with, re-fetch it with --refresh"
+            ),
+        )),
+        None if allow_unpinned => {
+            eprintln!("warning: no pinned SHA-256 for {url}\n    {digest}  {url}");
+            Ok(())
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "no pinned SHA-256 for {url}\n    got {digest}\ncheck the file against upstream and pin it in sources.sha256, or pass --allow-unpinned"
+            ),
+        )),
+    }
+}
+
+/// The digest pinned for `url` in `manifest`.
+fn expected<'a>(manifest: &'a str, url: &str) -> Option<&'a str> {
+    manifest
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, pinned)| pinned.trim() == url)
+        .map(|(digest, _)| digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SHA-256 of "hello\n".
+    const MANIFEST: &str = "
+# comment
+5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03  https://example.com/hello.txt
+";
+
+    #[test]
+    fn test_verify() {
+        assert!(verify_with(MANIFEST, "https://example.com/hello.txt", "hello\n", false).is_ok());
+        let err = verify_with(MANIFEST, "https://example.com/hello.txt", "hell", true).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("--refresh"));
+    }
+
+    #[test]
+    fn test_verify_unpinned() {
+        let err =
+            verify_with(MANIFEST, "https://example.com/other.txt", "anything", false).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("--allow-unpinned"));
+        assert!(verify_with(MANIFEST, "https://example.com/other.txt", "anything", true).is_ok());
+    }
+
+    #[test]
+    fn test_verify_data_dir() {
+        let dir = std::env::temp_dir().join(format!("emojeez-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // Without a manifest, the files of a data directory are used as they are
+        assert!(verify_data_dir(&dir, "hello.txt", "anything").is_ok());
+
+        fs::write(
+            dir.join(DATA_DIR_MANIFEST),
+            "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03  hello.txt\n",
+        )
+        .unwrap();
+        assert!(verify_data_dir(&dir, "hello.txt", "hello\n").is_ok());
+        assert!(verify_data_dir(&dir, "hello.txt", "hell").is_err());
+        assert!(verify_data_dir(&dir, "other.txt", "anything").is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
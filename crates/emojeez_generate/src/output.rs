@@ -1,15 +1,32 @@
-use std::fmt::Write;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt::Write,
+};
 
-use unicode_types::{Emoji, Entry, Group, SkinTone, Status, Version};
+use unicode_types::{Emoji, Entry, Group, SequenceKind, SkinTone, Status, Version, fold};
 
-use crate::util::{struct_name, struct_package};
+use crate::{
+    cldr::{Annotation, Locale, regions::Region},
+    unicode::properties::Properties,
+    util::{readable_literal, struct_name, struct_package},
+};
 
 const TAB: &str = "    ";
 
+type OwnedEmoji = Emoji<String, Vec<String>>;
+
 #[allow(clippy::too_many_lines)]
-pub fn generate_rust_code(data: &Vec<unicode_types::Emoji<String, Vec<String>>>) -> String {
+pub fn generate_rust_code(
+    data: &[OwnedEmoji],
+    properties: &Properties,
+    regions: &[Region],
+    locales: &[Locale],
+) -> String {
     let mut code = String::from(&format!(
-        r"use {}::{{{}, {}, {}, {}, {}, {}}};
+        r"#[allow(unused_imports)]
+use {}::{{{}, {}, {}, {}, {}, {}, {}}};
+
+use crate::index::Index;
 
 pub const EMOJIS: &[{}<&'static str, &'static [&'static str]>] = &[
 ",
@@ -17,6 +34,7 @@ pub const EMOJIS: &[{}<&'static str, &'static [&'static str]>] = &[
         struct_name::<Emoji<String, Vec<String>>>(),
         struct_name::<Entry<String, Vec<String>>>(),
         struct_name::<Group>(),
+        struct_name::<SequenceKind>(),
         struct_name::<SkinTone>(),
         struct_name::<Status>(),
         struct_name::<Version>(),
@@ -25,6 +43,13 @@ pub const EMOJIS: &[{}<&'static str, &'static [&'static str]>] = &[
 
     for emoji in data {
         let mut indent_count = 1;
+        writeln!(
+            code,
+            "{}#[cfg({})]",
+            TAB.repeat(indent_count),
+            feature_condition(emoji)
+        )
+        .unwrap();
         writeln!(
             code,
             "{}{} {{",
@@ -46,6 +71,7 @@ pub const EMOJIS: &[{}<&'static str, &'static [&'static str]>] = &[
             r#"{}group: {}::{:?},
 {}subgroup: "{}",
 {}status: {}::{:?},
+{}sequence_kind: {}::{:?},
 {}unicode_version: {} {{ major: {}, minor: {} }},
 {}emoji: "{}",
 {}name: "{}","#,
@@ -58,6 +84,9 @@ pub const EMOJIS: &[{}<&'static str, &'static [&'static str]>] = &[
             struct_name::<Status>(),
             emoji.entry.status,
             TAB.repeat(indent_count),
+            struct_name::<SequenceKind>(),
+            emoji.entry.sequence_kind,
+            TAB.repeat(indent_count),
             struct_name::<Version>(),
             emoji.entry.unicode_version.major,
             emoji.entry.unicode_version.minor,
@@ -68,48 +97,50 @@ pub const EMOJIS: &[{}<&'static str, &'static [&'static str]>] = &[
         )
         .unwrap();
 
-        if let Some(ios) = &emoji.entry.ios_version {
+        let gemoji_fields = [
+            format!(
+                "ios_version: {},",
+                emoji.entry.ios_version.map_or_else(
+                    || "None".to_owned(),
+                    |ios| format!(
+                        "Some({} {{ major: {}, minor: {} }})",
+                        struct_name::<Version>(),
+                        ios.major,
+                        ios.minor
+                    )
+                )
+            ),
+            format!("tags: {},", string_slice(&emoji.entry.tags)),
+            format!("aliases: {},", string_slice(&emoji.entry.aliases)),
+        ];
+        write_gated_fields(
+            &mut code,
+            indent_count,
+            "gemoji-metadata",
+            &gemoji_fields,
+            &["ios_version: None,", "tags: &[],", "aliases: &[],"],
+        );
+
+        indent_count -= 1;
+        writeln!(code, "{}}},", TAB.repeat(indent_count)).unwrap();
+
+        if emoji.skin_tones > 1 {
             writeln!(
                 code,
-                "{}ios_version: Some({} {{ major: {}, minor: {} }}),",
+                "{}skin_tones: if cfg!(feature = \"skin-tones\") {{ {} }} else {{ 1 }},",
                 TAB.repeat(indent_count),
-                struct_name::<Version>(),
-                ios.major,
-                ios.minor
+                emoji.skin_tones
             )
             .unwrap();
         } else {
-            writeln!(code, "{}ios_version: None,", TAB.repeat(indent_count),).unwrap();
-        }
-
-        write!(code, "{}tags: &[", TAB.repeat(indent_count)).unwrap();
-        for (i, tag) in emoji.entry.tags.iter().enumerate() {
-            if i > 0 {
-                code.push_str(", ");
-            }
-            write!(code, "\"{tag}\"").unwrap();
-        }
-        writeln!(code, "],").unwrap();
-
-        write!(code, "{}aliases: &[", TAB.repeat(indent_count)).unwrap();
-        for (i, alias) in emoji.entry.aliases.iter().enumerate() {
-            if i > 0 {
-                code.push_str(", ");
-            }
-            write!(code, "\"{alias}\"").unwrap();
+            writeln!(
+                code,
+                "{}skin_tones: {},",
+                TAB.repeat(indent_count),
+                emoji.skin_tones
+            )
+            .unwrap();
         }
-        writeln!(code, "],").unwrap();
-
-        indent_count -= 1;
-        writeln!(code, "{}}},", TAB.repeat(indent_count)).unwrap();
-
-        writeln!(
-            code,
-            "{}skin_tones: {},",
-            TAB.repeat(indent_count),
-            emoji.skin_tones
-        )
-        .unwrap();
 
         if let Some(tone) = &emoji.skin_tone {
             writeln!(
@@ -124,11 +155,421 @@ pub const EMOJIS: &[{}<&'static str, &'static [&'static str]>] = &[
             writeln!(code, "{}skin_tone: None,", TAB.repeat(indent_count)).unwrap();
         }
 
-        writeln!(code, "{}variations: &[],", TAB.repeat(indent_count)).unwrap();
+        write_gated_fields(
+            &mut code,
+            indent_count,
+            "variations",
+            &[format!("variations: {},", string_slice(&emoji.variations))],
+            &["variations: &[],"],
+        );
         indent_count -= 1;
         writeln!(code, "{}}},", TAB.repeat(indent_count)).unwrap();
     }
 
     code.push_str("];\n");
+
+    let max_len = data
+        .iter()
+        .flat_map(|emoji| std::iter::once(&emoji.entry.emoji).chain(&emoji.variations))
+        .map(String::len)
+        .max()
+        .unwrap_or(0);
+    writeln!(code, "\npub const MAX_EMOJI_LEN: usize = {max_len};").unwrap();
+
+    write_runs(&mut code, data);
+
+    write_index(
+        &mut code,
+        "EMOJI_INDEX",
+        data.iter().enumerate().flat_map(|(i, emoji)| {
+            std::iter::once(&emoji.entry.emoji)
+                .chain(&emoji.variations)
+                .map(move |s| (s.as_str(), i))
+        }),
+    );
+    write_index(
+        &mut code,
+        "SHORTCODE_INDEX",
+        data.iter().enumerate().flat_map(|(i, emoji)| {
+            emoji
+                .entry
+                .aliases
+                .iter()
+                .map(move |alias| (alias.as_str(), i))
+        }),
+    );
+    write_index(
+        &mut code,
+        "NAME_INDEX",
+        data.iter()
+            .enumerate()
+            .map(|(i, emoji)| (emoji.entry.name.as_str(), i)),
+    );
+
+    code.push_str(
+        "
+pub type Tokens = [(&'static str, &'static [u16])];
+
+pub type Annotations = [(&'static str, &'static [&'static str])];
+",
+    );
+
+    write_tokens(
+        &mut code,
+        "NAME_TOKENS",
+        data,
+        None,
+        data.iter()
+            .enumerate()
+            .flat_map(|(i, emoji)| tokenize(&emoji.entry.name).map(move |t| (t, i))),
+    );
+    write_tokens(
+        &mut code,
+        "ALIAS_TOKENS",
+        data,
+        Some("gemoji-metadata"),
+        data.iter().enumerate().flat_map(|(i, emoji)| {
+            emoji
+                .entry
+                .aliases
+                .iter()
+                .map(move |alias| (fold(alias), i))
+        }),
+    );
+    write_tokens(
+        &mut code,
+        "TAG_TOKENS",
+        data,
+        Some("gemoji-metadata"),
+        data.iter().enumerate().flat_map(|(i, emoji)| {
+            emoji
+                .entry
+                .tags
+                .iter()
+                .flat_map(|tag| tokenize(tag))
+                .map(move |t| (t, i))
+        }),
+    );
+    write_tokens(
+        &mut code,
+        "SUBGROUP_TOKENS",
+        data,
+        None,
+        data.iter()
+            .enumerate()
+            .flat_map(|(i, emoji)| tokenize(&emoji.entry.subgroup).map(move |t| (t, i))),
+    );
+
+    write_annotations(&mut code, data, locales);
+
+    write_zwj_sequences(&mut code, data);
+
+    for (name, ranges) in [
+        ("EMOJI", &properties.emoji),
+        ("EMOJI_PRESENTATION", &properties.emoji_presentation),
+        ("EMOJI_MODIFIER", &properties.emoji_modifier),
+        ("EMOJI_MODIFIER_BASE", &properties.emoji_modifier_base),
+        ("EMOJI_COMPONENT", &properties.emoji_component),
+        ("EXTENDED_PICTOGRAPHIC", &properties.extended_pictographic),
+    ] {
+        write_ranges(&mut code, name, ranges);
+    }
+
+    code.push_str("\npub static REGIONS: &[(&str, &[&str])] = &[\n");
+    for region in regions {
+        writeln!(
+            code,
+            "{TAB}(\"{}\", {}),",
+            region.name,
+            string_slice(&region.territories)
+        )
+        .unwrap();
+    }
+    code.push_str("];\n");
+
     code
 }
+
+/// Writes a property of emoji-data.txt as sorted, non-overlapping ranges for binary search.
+fn write_ranges(code: &mut String, name: &str, ranges: &[(char, char)]) {
+    writeln!(code, "\npub static {name}: &[(char, char)] = &[").unwrap();
+    for &(first, last) in ranges {
+        writeln!(
+            code,
+            "{TAB}('\\u{{{:x}}}', '\\u{{{:x}}}'),",
+            u32::from(first),
+            u32::from(last)
+        )
+        .unwrap();
+    }
+    code.push_str("];\n");
+}
+
+/// Writes the RGI ZWJ sequences, sorted for binary search. Unlike `EMOJIS`, they are not trimmed by features.
+fn write_zwj_sequences(code: &mut String, data: &[OwnedEmoji]) {
+    let mut sequences: Vec<_> = data
+        .iter()
+        .filter(|emoji| emoji.entry.sequence_kind == SequenceKind::Zwj)
+        .map(|emoji| emoji.entry.emoji.as_str())
+        .collect();
+    sequences.sort_unstable();
+
+    code.push_str("\npub static ZWJ_SEQUENCES: &[&str] = &[\n");
+    for sequence in sequences {
+        writeln!(code, "{TAB}\"{}\",", escape(sequence)).unwrap();
+    }
+    code.push_str("];\n");
+}
+
+/// Splits `s` into the [`fold`]ed alphanumeric words used by the search index.
+fn tokenize(s: &str) -> impl Iterator<Item = String> {
+    fold(s)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_owned)
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Writes the tables of `tokens`, see [`write_token_tables`].
+fn write_tokens(
+    code: &mut String,
+    name: &str,
+    data: &[OwnedEmoji],
+    feature: Option<&str>,
+    tokens: impl Iterator<Item = (String, usize)>,
+) {
+    writeln!(code, "\npub const {name}: &[&Tokens] = &[").unwrap();
+    write_token_tables(code, 1, data, feature, tokens);
+    code.push_str("];\n");
+}
+
+/// Writes `tokens` as one table per [`feature_condition`], gated like `EMOJIS`, so that the tokens of emoji left out
+/// by features are not compiled in either. Each table is sorted for prefix search, each token with the sorted ids of
+/// the emoji containing it.
+///
+/// With a `feature`, the tables also require that feature.
+fn write_token_tables(
+    code: &mut String,
+    indent_count: usize,
+    data: &[OwnedEmoji],
+    feature: Option<&str>,
+    tokens: impl Iterator<Item = (String, usize)>,
+) {
+    let mut tables: BTreeMap<String, BTreeMap<String, BTreeSet<usize>>> = BTreeMap::new();
+    for (token, i) in tokens {
+        tables
+            .entry(feature_condition(&data[i]))
+            .or_default()
+            .entry(token)
+            .or_default()
+            .insert(i);
+    }
+
+    let indent = TAB.repeat(indent_count);
+    for (condition, table) in tables {
+        let condition = match feature {
+            Some(feature) => all_of(&condition, &format!("feature = \"{feature}\"")),
+            None => condition,
+        };
+        writeln!(code, "{indent}#[cfg({condition})]\n{indent}&[").unwrap();
+        for (token, postings) in table {
+            write!(code, "{indent}{TAB}(\"{token}\", &[").unwrap();
+            for (i, posting) in postings.into_iter().enumerate() {
+                if i > 0 {
+                    code.push_str(", ");
+                }
+                write!(code, "{}", u16::try_from(posting).unwrap()).unwrap();
+            }
+            writeln!(code, "]),").unwrap();
+        }
+        writeln!(code, "{indent}],").unwrap();
+    }
+}
+
+/// Writes, for each locale, the CLDR name and keywords of every emoji in `EMOJIS` order and gated like it, and search
+/// token tables over both. Locales are only compiled in with the `cldr-annotations` feature.
+fn write_annotations(code: &mut String, data: &[OwnedEmoji], locales: &[Locale]) {
+    let annotation = |locale: &'_ Locale, emoji: &OwnedEmoji| {
+        locale.get(&emoji.entry.emoji).cloned().unwrap_or_default()
+    };
+
+    writeln!(
+        code,
+        "\npub const ANNOTATIONS: &[(&str, &Annotations)] = &["
+    )
+    .unwrap();
+    for locale in locales {
+        writeln!(code, "{TAB}#[cfg(feature = \"cldr-annotations\")]").unwrap();
+        writeln!(code, "{TAB}(\"{}\", &[", locale.locale).unwrap();
+        for emoji in data {
+            let Annotation { name, keywords } = annotation(locale, emoji);
+            writeln!(code, "{TAB}{TAB}#[cfg({})]", feature_condition(emoji)).unwrap();
+            write!(code, "{TAB}{TAB}(\"{}\", &[", escape(&name)).unwrap();
+            for (i, keyword) in keywords.iter().enumerate() {
+                if i > 0 {
+                    code.push_str(", ");
+                }
+                write!(code, "\"{}\"", escape(keyword)).unwrap();
+            }
+            writeln!(code, "]),").unwrap();
+        }
+        writeln!(code, "{TAB}]),").unwrap();
+    }
+    code.push_str("];\n");
+
+    writeln!(
+        code,
+        "\npub const ANNOTATION_TOKENS: &[(&str, &[&Tokens])] = &["
+    )
+    .unwrap();
+    for locale in locales {
+        writeln!(code, "{TAB}#[cfg(feature = \"cldr-annotations\")]").unwrap();
+        writeln!(code, "{TAB}(\"{}\", &[", locale.locale).unwrap();
+        write_token_tables(
+            code,
+            2,
+            data,
+            None,
+            data.iter().enumerate().flat_map(|(i, emoji)| {
+                let Annotation { name, keywords } = annotation(locale, emoji);
+                std::iter::once(name)
+                    .chain(keywords)
+                    .flat_map(|s| tokenize(&s).collect::<Vec<_>>())
+                    .map(move |t| (t, i))
+            }),
+        );
+        writeln!(code, "{TAB}]),").unwrap();
+    }
+    code.push_str("];\n");
+}
+
+/// Escapes a string for a Rust literal. CLDR annotations, unlike the Unicode and gemoji data, may contain quotes.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Writes a perfect-hash index from `keys` to emoji ids.
+///
+/// Only the ids are stored: `emojeez` confirms a candidate against the fields of its entry, so the key strings of
+/// emoji left out by features are not compiled in. When a key is seen more than once, the first id wins.
+fn write_index<'a>(code: &mut String, name: &str, keys: impl Iterator<Item = (&'a str, usize)>) {
+    let mut seen = HashSet::new();
+    let entries: Vec<_> = keys.filter(|(key, _)| seen.insert(*key)).collect();
+    let state =
+        phf_generator::generate_hash(&entries.iter().map(|(key, _)| key).collect::<Vec<_>>());
+
+    writeln!(code, "\npub static {name}: Index = Index {{").unwrap();
+    writeln!(code, "{TAB}key: {},", readable_literal(state.key)).unwrap();
+    writeln!(code, "{TAB}disps: &[").unwrap();
+    for (d1, d2) in &state.disps {
+        writeln!(code, "{TAB}{TAB}({d1}, {d2}),").unwrap();
+    }
+    writeln!(code, "{TAB}],").unwrap();
+    write!(code, "{TAB}ids: &[").unwrap();
+    for (n, &i) in state.map.iter().enumerate() {
+        if n > 0 {
+            code.push_str(", ");
+        }
+        write!(code, "{}", u16::try_from(entries[i].1).unwrap()).unwrap();
+    }
+    writeln!(code, "],").unwrap();
+    code.push_str("};\n");
+}
+
+/// Writes the number of emoji in the dataset and, as runs of consecutive ids, whether the enabled features compile
+/// them into `EMOJIS`.
+fn write_runs(code: &mut String, data: &[OwnedEmoji]) {
+    writeln!(code, "\npub const DATASET_LEN: usize = {};", data.len()).unwrap();
+    writeln!(code, "\npub const RUNS: &[(u16, bool)] = &[").unwrap();
+    for run in data.chunk_by(|a, b| feature_condition(a) == feature_condition(b)) {
+        writeln!(
+            code,
+            "{TAB}({}, cfg!({})),",
+            run.len(),
+            feature_condition(&run[0])
+        )
+        .unwrap();
+    }
+    code.push_str("];\n");
+}
+
+/// The `cfg` predicate under which `emoji` is compiled in.
+fn feature_condition(emoji: &OwnedEmoji) -> String {
+    let group = format!("feature = \"{}\"", group_feature(emoji.entry.group));
+    if emoji
+        .skin_tone
+        .is_some_and(|tone| tone != SkinTone::Default)
+    {
+        format!("all({group}, feature = \"skin-tones\")")
+    } else {
+        group
+    }
+}
+
+/// Joins two `cfg` predicates, without nesting `all`.
+fn all_of(condition: &str, other: &str) -> String {
+    let conditions = condition
+        .strip_prefix("all(")
+        .and_then(|conditions| conditions.strip_suffix(')'))
+        .unwrap_or(condition);
+    format!("all({conditions}, {other})")
+}
+
+const fn group_feature(group: Group) -> &'static str {
+    match group {
+        Group::SmileysAndEmotion => "group-smileys",
+        Group::PeopleAndBody => "group-people",
+        Group::AnimalsAndNature => "group-animals",
+        Group::FoodAndDrink => "group-food",
+        Group::TravelAndPlaces => "group-travel",
+        Group::Activities => "group-activities",
+        Group::Objects => "group-objects",
+        Group::Symbols => "group-symbols",
+        Group::Flags => "group-flags",
+        Group::Component => unreachable!(),
+    }
+}
+
+/// Writes struct fields that are only compiled in with `feature`, and their `fallback` without it.
+///
+/// Fields that already equal their fallback are written once, ungated.
+fn write_gated_fields(
+    code: &mut String,
+    indent_count: usize,
+    feature: &str,
+    fields: &[String],
+    fallback: &[&str],
+) {
+    let indent = TAB.repeat(indent_count);
+    if fields
+        .iter()
+        .zip(fallback)
+        .all(|(field, fallback)| field == fallback)
+    {
+        for field in fields {
+            writeln!(code, "{indent}{field}").unwrap();
+        }
+        return;
+    }
+    for field in fields {
+        writeln!(
+            code,
+            "{indent}#[cfg(feature = \"{feature}\")]\n{indent}{field}"
+        )
+        .unwrap();
+    }
+    for field in fallback {
+        writeln!(
+            code,
+            "{indent}#[cfg(not(feature = \"{feature}\"))]\n{indent}{field}"
+        )
+        .unwrap();
+    }
+}
+
+/// Formats `items` as a `&[&str]` literal.
+fn string_slice(items: &[String]) -> String {
+    let items: Vec<_> = items.iter().map(|item| format!("\"{item}\"")).collect();
+    format!("&[{}]", items.join(", "))
+}
@@ -0,0 +1,342 @@
+//! Changes between two generated datasets, for release notes.
+
+use std::{collections::HashMap, fmt};
+
+use serde::Serialize;
+use unicode_types::{Emoji, SkinTone};
+
+type OwnedEmoji = Emoji<String, Vec<String>>;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub emoji: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Renamed {
+    pub emoji: String,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SubgroupChange {
+    pub emoji: String,
+    pub name: String,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SkinToneVariants {
+    pub emoji: String,
+    pub name: String,
+    pub added: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AliasChange {
+    pub emoji: String,
+    pub name: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// The changes from the `old` dataset to the `new` one, matching emoji by their fully-qualified sequence.
+///
+/// New toned variants are listed under their default emoji in `skin_tones` rather than in `added`, and removed toned
+/// variants are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub old: String,
+    pub new: String,
+    pub added: Vec<Summary>,
+    pub removed: Vec<Summary>,
+    pub renamed: Vec<Renamed>,
+    pub subgroups: Vec<SubgroupChange>,
+    pub skin_tones: Vec<SkinToneVariants>,
+    pub aliases: Vec<AliasChange>,
+}
+
+impl Report {
+    pub fn new(
+        old_label: String,
+        old: &[OwnedEmoji],
+        new_label: String,
+        new: &[OwnedEmoji],
+    ) -> Self {
+        let index = |data: &[OwnedEmoji]| -> HashMap<String, usize> {
+            data.iter()
+                .enumerate()
+                .map(|(i, emoji)| (emoji.entry.emoji.clone(), i))
+                .collect()
+        };
+        let old_index = index(old);
+        let new_index = index(new);
+        let is_toned = |emoji: &OwnedEmoji| {
+            emoji
+                .skin_tone
+                .is_some_and(|tone| tone != SkinTone::Default)
+        };
+        let summary = |emoji: &OwnedEmoji| Summary {
+            emoji: emoji.entry.emoji.clone(),
+            name: emoji.entry.name.clone(),
+        };
+
+        let mut report = Self {
+            old: old_label,
+            new: new_label,
+            ..Self::default()
+        };
+
+        report.removed = old
+            .iter()
+            .filter(|emoji| !is_toned(emoji) && !new_index.contains_key(&emoji.entry.emoji))
+            .map(summary)
+            .collect();
+
+        // The most recent default emoji, which the toned entries that follow it belong to.
+        let mut base: Option<&OwnedEmoji> = None;
+        for emoji in new {
+            if !is_toned(emoji) {
+                base = Some(emoji);
+            }
+            let Some(&i) = old_index.get(&emoji.entry.emoji) else {
+                match base {
+                    Some(base) if is_toned(emoji) => {
+                        if report
+                            .skin_tones
+                            .last()
+                            .is_none_or(|variants| variants.emoji != base.entry.emoji)
+                        {
+                            report.skin_tones.push(SkinToneVariants {
+                                emoji: base.entry.emoji.clone(),
+                                name: base.entry.name.clone(),
+                                added: Vec::new(),
+                            });
+                        }
+                        report
+                            .skin_tones
+                            .last_mut()
+                            .unwrap()
+                            .added
+                            .push(emoji.entry.emoji.clone());
+                    }
+                    _ => report.added.push(summary(emoji)),
+                }
+                continue;
+            };
+
+            let previous = &old[i];
+            if previous.entry.name != emoji.entry.name {
+                report.renamed.push(Renamed {
+                    emoji: emoji.entry.emoji.clone(),
+                    old: previous.entry.name.clone(),
+                    new: emoji.entry.name.clone(),
+                });
+            }
+            if previous.entry.subgroup != emoji.entry.subgroup {
+                report.subgroups.push(SubgroupChange {
+                    emoji: emoji.entry.emoji.clone(),
+                    name: emoji.entry.name.clone(),
+                    old: previous.entry.subgroup.clone(),
+                    new: emoji.entry.subgroup.clone(),
+                });
+            }
+            let added: Vec<_> = emoji
+                .entry
+                .aliases
+                .iter()
+                .filter(|a| !previous.entry.aliases.contains(a))
+                .cloned()
+                .collect();
+            let removed: Vec<_> = previous
+                .entry
+                .aliases
+                .iter()
+                .filter(|a| !emoji.entry.aliases.contains(a))
+                .cloned()
+                .collect();
+            if !added.is_empty() || !removed.is_empty() {
+                report.aliases.push(AliasChange {
+                    emoji: emoji.entry.emoji.clone(),
+                    name: emoji.entry.name.clone(),
+                    added,
+                    removed,
+                });
+            }
+        }
+
+        report
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.subgroups.is_empty()
+            && self.skin_tones.is_empty()
+            && self.aliases.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Changes from {} to {}", self.old, self.new)?;
+        if self.is_empty() {
+            return writeln!(f, "\nNo changes.");
+        }
+
+        section(f, "Added", &self.added, |f, s| {
+            write!(f, "{} {}", s.emoji, s.name)
+        })?;
+        section(f, "Removed", &self.removed, |f, s| {
+            write!(f, "{} {}", s.emoji, s.name)
+        })?;
+        section(f, "Renamed", &self.renamed, |f, r| {
+            write!(f, "{} {} -> {}", r.emoji, r.old, r.new)
+        })?;
+        section(f, "Subgroup changes", &self.subgroups, |f, s| {
+            write!(f, "{} {}: {} -> {}", s.emoji, s.name, s.old, s.new)
+        })?;
+        section(f, "New skin tone variants", &self.skin_tones, |f, s| {
+            write!(f, "{} {}: {}", s.emoji, s.name, s.added.join(" "))
+        })?;
+        section(f, "Alias changes", &self.aliases, |f, a| {
+            write!(f, "{} {}:", a.emoji, a.name)?;
+            for alias in &a.added {
+                write!(f, " +{alias}")?;
+            }
+            for alias in &a.removed {
+                write!(f, " -{alias}")?;
+            }
+            Ok(())
+        })
+    }
+}
+
+fn section<T>(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    items: &[T],
+    item: impl Fn(&mut fmt::Formatter<'_>, &T) -> fmt::Result,
+) -> fmt::Result {
+    if items.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "\n{title} ({}):", items.len())?;
+    for i in items {
+        write!(f, "  ")?;
+        item(f, i)?;
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use unicode_types::{Entry, Group, SequenceKind, Status, Version};
+
+    use super::*;
+
+    fn emoji(
+        emoji: &str,
+        name: &str,
+        subgroup: &str,
+        aliases: &[&str],
+        skin_tone: Option<SkinTone>,
+    ) -> OwnedEmoji {
+        Emoji {
+            entry: Entry {
+                group: Group::PeopleAndBody,
+                subgroup: subgroup.to_owned(),
+                status: Status::FullyQualified,
+                sequence_kind: SequenceKind::Basic,
+                unicode_version: Version { major: 1, minor: 0 },
+                emoji: emoji.to_owned(),
+                name: name.to_owned(),
+                ios_version: None,
+                tags: Vec::new(),
+                aliases: aliases.iter().map(ToString::to_string).collect(),
+            },
+            skin_tones: 1,
+            skin_tone,
+            variations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let old = [
+            emoji("👋", "waving hand", "hand-fingers-open", &["wave"], None),
+            emoji("🤚", "raised back of hand", "hand-fingers-open", &[], None),
+            emoji(
+                "🖐",
+                "hand with fingers splayed",
+                "hand-fingers-open",
+                &[],
+                None,
+            ),
+        ];
+        let new = [
+            emoji(
+                "👋",
+                "waving hand",
+                "hand-fingers-open",
+                &["wave", "hello"],
+                Some(SkinTone::Default),
+            ),
+            emoji(
+                "👋🏻",
+                "waving hand: light skin tone",
+                "hand-fingers-open",
+                &[],
+                Some(SkinTone::Light),
+            ),
+            emoji("🤚", "back of hand", "hands", &[], None),
+            emoji("🫯", "fight cloud", "face-costume", &[], None),
+        ];
+
+        let report = Report::new("old".to_owned(), &old, "new".to_owned(), &new);
+        assert_eq!(
+            report
+                .added
+                .iter()
+                .map(|s| s.emoji.as_str())
+                .collect::<Vec<_>>(),
+            ["🫯"]
+        );
+        assert_eq!(
+            report
+                .removed
+                .iter()
+                .map(|s| s.emoji.as_str())
+                .collect::<Vec<_>>(),
+            ["🖐"]
+        );
+        assert_eq!(report.renamed[0].new, "back of hand");
+        assert_eq!(report.subgroups[0].new, "hands");
+        assert_eq!(report.skin_tones[0].emoji, "👋");
+        assert_eq!(report.skin_tones[0].added, ["👋🏻"]);
+        assert_eq!(report.aliases[0].added, ["hello"]);
+
+        let text = report.to_string();
+        assert!(text.contains("Added (1):\n  🫯 fight cloud\n"));
+        assert!(text.contains("Alias changes (1):\n  👋 waving hand: +hello\n"));
+    }
+
+    #[test]
+    fn test_report_empty() {
+        let data = [emoji(
+            "👋",
+            "waving hand",
+            "hand-fingers-open",
+            &["wave"],
+            None,
+        )];
+        let report = Report::new("a".to_owned(), &data, "b".to_owned(), &data);
+        assert!(report.is_empty());
+        assert_eq!(report.to_string(), "Changes from a to b\n\nNo changes.\n");
+    }
+}
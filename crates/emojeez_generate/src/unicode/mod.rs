@@ -1,29 +1,43 @@
-use std::error::Error;
+pub mod properties;
 
-use unicode_types::{Emoji, Entry, Group, SkinTone, Status, Version};
+use std::{collections::HashMap, error::Error};
+
+use unicode_types::{Emoji, Entry, Group, SequenceKind, SkinTone, Status, Version};
 
 use crate::github::Gemoji;
-use crate::util;
+use crate::util::{Release, Source};
 
-pub const VERSION_MAJOR: usize = 17;
-pub const VERSION_MINOR: usize = 0;
-pub const VERSION_PATCH: usize = 0;
+/// The Unicode emoji version generated by default.
+pub const VERSION: Release = Release {
+    major: 17,
+    minor: 0,
+    patch: 0,
+};
 
 type OwnedEntry = Entry<String, Vec<String>>;
 type OwnedEmoji = Emoji<String, Vec<String>>;
 
-fn unicode_url() -> String {
-    format!(
-        "https://unicode.org/Public/{VERSION_MAJOR}.{VERSION_MINOR}.{VERSION_PATCH}/emoji/emoji-test.txt"
-    )
+/// The kind of each RGI emoji, keyed by its code points without U+FE0F so that variations find it too.
+type SequenceKinds = HashMap<String, SequenceKind>;
+
+/// The emoji files moved from `Public/emoji/<major>.<minor>/` into the versioned Unicode directory in 17.0.
+fn unicode_url(version: Release, file_name: &str) -> String {
+    if version.major >= 17 {
+        format!("https://unicode.org/Public/{version}/emoji/{file_name}")
+    } else {
+        format!(
+            "https://unicode.org/Public/emoji/{}.{}/{file_name}",
+            version.major, version.minor
+        )
+    }
 }
 
-pub fn parse_data(data: &str) -> Result<Vec<OwnedEntry>, Box<dyn Error>> {
-    let entries = parse_emoji_data(data)?;
+pub fn parse_data(data: &str, kinds: &SequenceKinds) -> Result<Vec<OwnedEntry>, Box<dyn Error>> {
+    let entries = parse_emoji_data(data, kinds)?;
     Ok(entries)
 }
 
-fn parse_emoji_data(data: &str) -> Result<Vec<OwnedEntry>, Box<dyn Error>> {
+fn parse_emoji_data(data: &str, kinds: &SequenceKinds) -> Result<Vec<OwnedEntry>, Box<dyn Error>> {
     let mut entries = Vec::new();
     let mut group: &str = "";
     let mut subgroup = String::new();
@@ -47,13 +61,23 @@ fn parse_emoji_data(data: &str) -> Result<Vec<OwnedEntry>, Box<dyn Error>> {
         if subgroup.is_empty() {
             return Err("missing subgroup".into());
         }
-        let entry = parse_entry(Group::try_from(group.to_string())?, subgroup.clone(), line)?;
+        let entry = parse_entry(
+            Group::try_from(group.to_string())?,
+            subgroup.clone(),
+            line,
+            kinds,
+        )?;
         entries.push(entry);
     }
     Ok(entries)
 }
 
-fn parse_entry(group: Group, subgroup: String, line: &str) -> Result<OwnedEntry, Box<dyn Error>> {
+fn parse_entry(
+    group: Group,
+    subgroup: String,
+    line: &str,
+    kinds: &SequenceKinds,
+) -> Result<OwnedEntry, Box<dyn Error>> {
     let (code_points, rest) = line.split_once(';').ok_or("expected code points")?;
     let (status, rest) = rest.split_once('#').ok_or("expected status")?;
     let mut rest = rest.trim().splitn(3, char::is_whitespace);
@@ -64,14 +88,19 @@ fn parse_entry(group: Group, subgroup: String, line: &str) -> Result<OwnedEntry,
         return Err("unexpected extra data".into());
     }
 
-    if emoji != String::from_iter(parse_code_points(code_points)?) {
+    let code_points = parse_code_points(code_points)?;
+    if emoji != String::from_iter(&code_points) {
         return Err("emoji mismatch".into());
     }
+    let sequence_kind = *kinds
+        .get(&sequence_key(&code_points))
+        .ok_or_else(|| format!("no sequence kind for '{name}'"))?;
 
     Ok(Entry {
         group,
         subgroup,
         status: parse_status(status.trim())?,
+        sequence_kind,
         emoji: emoji.to_owned(),
         unicode_version: parse_unicode_version(unicode_version.trim())?,
         name: name.to_owned(),
@@ -101,19 +130,69 @@ fn parse_unicode_version(s: &str) -> Result<Version, Box<dyn Error>> {
 }
 
 fn parse_code_points(s: &str) -> Result<Vec<char>, Box<dyn Error>> {
-    s.split_ascii_whitespace()
-        .map(|cp| {
-            let scalar = u32::from_str_radix(cp, 16)?;
-            char::from_u32(scalar).ok_or_else(|| "invalid Unicode scalar value".into())
-        })
-        .collect()
+    s.split_ascii_whitespace().map(parse_code_point).collect()
+}
+
+fn parse_code_point(s: &str) -> Result<char, Box<dyn Error>> {
+    let scalar = u32::from_str_radix(s, 16)?;
+    char::from_u32(scalar).ok_or_else(|| "invalid Unicode scalar value".into())
+}
+
+fn sequence_key(code_points: &[char]) -> String {
+    code_points.iter().filter(|&&c| c != '\u{fe0f}').collect()
 }
 
-pub fn build() -> Result<Vec<OwnedEmoji>, Box<dyn Error>> {
-    let gemojis: Vec<Gemoji> = crate::github::build()?;
+/// Adds the sequences of emoji-sequences.txt or emoji-zwj-sequences.txt to `kinds`, expanding code point ranges.
+fn parse_sequences(data: &str, kinds: &mut SequenceKinds) -> Result<(), Box<dyn Error>> {
+    for line in data.lines() {
+        let line = line.split_once('#').map_or(line, |(data, _)| data).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split(';').map(str::trim);
+        let code_points = fields.next().ok_or("expected code points")?;
+        let kind = parse_sequence_kind(fields.next().ok_or("expected type field")?)?;
+
+        if let Some((first, last)) = code_points.split_once("..") {
+            for scalar in u32::from(parse_code_point(first)?)..=u32::from(parse_code_point(last)?) {
+                let c = char::from_u32(scalar).ok_or("invalid Unicode scalar value")?;
+                kinds.insert(c.to_string(), kind);
+            }
+        } else {
+            kinds.insert(sequence_key(&parse_code_points(code_points)?), kind);
+        }
+    }
+    Ok(())
+}
+
+fn parse_sequence_kind(s: &str) -> Result<SequenceKind, Box<dyn Error>> {
+    match s {
+        "Basic_Emoji" => Ok(SequenceKind::Basic),
+        "Emoji_Keycap_Sequence" => Ok(SequenceKind::Keycap),
+        "RGI_Emoji_Flag_Sequence" => Ok(SequenceKind::Flag),
+        "RGI_Emoji_Tag_Sequence" => Ok(SequenceKind::Tag),
+        "RGI_Emoji_Modifier_Sequence" => Ok(SequenceKind::Modifier),
+        "RGI_Emoji_ZWJ_Sequence" => Ok(SequenceKind::Zwj),
+        _ => Err(format!("invalid type field: {s:?}").into()),
+    }
+}
+
+pub fn build(
+    source: &Source,
+    version: Release,
+    gemojis: &[Gemoji],
+) -> Result<Vec<OwnedEmoji>, Box<dyn Error>> {
     let mut emojis: Vec<OwnedEmoji> = Vec::new();
-    let data = util::cached_download(&unicode_url())?;
-    for mut entry in parse_data(&data).map_err(|e| format!("Failed to parse data: {e}"))? {
+    // Index of the most recent fully-qualified emoji, which toned entries may not leave at the end.
+    let mut last: Option<usize> = None;
+    let mut kinds = SequenceKinds::new();
+    for file_name in ["emoji-sequences.txt", "emoji-zwj-sequences.txt"] {
+        let data = source.read(&unicode_url(version, file_name), file_name)?;
+        parse_sequences(&data, &mut kinds)
+            .map_err(|e| format!("Failed to parse {file_name}: {e}"))?;
+    }
+    let data = source.read(&unicode_url(version, "emoji-test.txt"), "emoji-test.txt")?;
+    for mut entry in parse_data(&data, &kinds).map_err(|e| format!("Failed to parse data: {e}"))? {
         if entry.group == Group::Component {
             continue;
         }
@@ -127,19 +206,20 @@ pub fn build() -> Result<Vec<OwnedEmoji>, Box<dyn Error>> {
         match entry.status {
             Status::Component => unreachable!(),
             Status::MinimallyQualified | Status::Unqualified => {
-                let last = emojis.last_mut().ok_or_else(|| {
+                let i = last.ok_or_else(|| {
                     format!(
                         "failed to find fully qualified variation for '{}'",
                         entry.name
                     )
                 })?;
-                last.variations.push(entry.emoji);
+                emojis[i].variations.push(entry.emoji);
             }
             Status::FullyQualified => {
                 let skin_tone = parse_skin_tone(&entry)?;
 
                 match skin_tone {
                     None | Some(SkinTone::Default) => {
+                        last = Some(emojis.len());
                         emojis.push(Emoji {
                             entry,
                             skin_tones: 1,
@@ -148,28 +228,18 @@ pub fn build() -> Result<Vec<OwnedEmoji>, Box<dyn Error>> {
                         });
                     }
                     Some(skin_tone) => {
-                        let i = {
-                            let (i, def) = emojis
-                                .iter_mut()
-                                .enumerate()
-                                .rev()
-                                .find(|(_, e)| {
-                                    matches!(e.skin_tone, None | Some(SkinTone::Default))
-                                        && e.entry.group == entry.group
-                                        && e.entry.subgroup == entry.subgroup
-                                })
-                                .ok_or_else(|| {
-                                    format!(
-                                        "failed to find the default skin tone for '{}'",
-                                        entry.name
-                                    )
-                                })?;
-                            def.skin_tone = Some(SkinTone::Default);
-                            def.skin_tones += 1;
-                            i
-                        };
-
-                        let j = emojis[i..].partition_point(|e| e.skin_tone < Some(skin_tone));
+                        let i = find_default(&emojis, &entry).ok_or_else(|| {
+                            format!("failed to find the default skin tone for '{}'", entry.name)
+                        })?;
+                        let def = &mut emojis[i];
+                        def.skin_tone = Some(SkinTone::Default);
+                        let run = def.skin_tones;
+                        def.skin_tones += 1;
+
+                        // Keep each default immediately followed by its toned entries, in `SkinTone` order.
+                        let j =
+                            emojis[i..i + run].partition_point(|e| e.skin_tone < Some(skin_tone));
+                        last = Some(i + j);
                         emojis.insert(
                             i + j,
                             Emoji {
@@ -188,6 +258,50 @@ pub fn build() -> Result<Vec<OwnedEmoji>, Box<dyn Error>> {
     Ok(emojis)
 }
 
+/// Finds the default-tone emoji that a toned `entry` belongs to.
+///
+/// The default is the same sequence without skin tone modifiers, possibly as one of its variations (e.g. 🕵🏻‍♂️ is
+/// 🕵️‍♂️ without the U+FE0F), or the emoji named like the entry without its skin tones (e.g. "people with bunny
+/// ears: light skin tone, dark skin tone" is 👯). Anything else falls back to the latest default of its subgroup.
+fn find_default(emojis: &[OwnedEmoji], entry: &OwnedEntry) -> Option<usize> {
+    let stripped: String = entry
+        .emoji
+        .chars()
+        .filter(|c| !('\u{1f3fb}'..='\u{1f3ff}').contains(c))
+        .collect();
+    let name = strip_skin_tone_name(&entry.name);
+
+    let candidates = || {
+        emojis.iter().enumerate().rev().filter(|(_, e)| {
+            matches!(e.skin_tone, None | Some(SkinTone::Default))
+                && e.entry.group == entry.group
+                && e.entry.subgroup == entry.subgroup
+        })
+    };
+
+    candidates()
+        .find(|(_, e)| e.entry.emoji == stripped || e.variations.contains(&stripped))
+        .or_else(|| candidates().find(|(_, e)| e.entry.name == name))
+        .or_else(|| candidates().next())
+        .map(|(i, _)| i)
+}
+
+/// Removes the skin tone qualifiers from a CLDR name, e.g. "person: light skin tone, beard" is "person: beard".
+fn strip_skin_tone_name(name: &str) -> String {
+    let Some((base, qualifiers)) = name.split_once(": ") else {
+        return name.to_owned();
+    };
+    let rest: Vec<_> = qualifiers
+        .split(", ")
+        .filter(|q| !q.ends_with("skin tone"))
+        .collect();
+    if rest.is_empty() {
+        base.to_owned()
+    } else {
+        format!("{base}: {}", rest.join(", "))
+    }
+}
+
 fn parse_skin_tone(entry: &Entry<String, Vec<String>>) -> Result<Option<SkinTone>, String> {
     let skin_tones: Vec<_> = entry
         .emoji
@@ -235,3 +349,86 @@ fn parse_skin_tone(entry: &Entry<String, Vec<String>>) -> Result<Option<SkinTone
 
     Ok(Some(skin_tone))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_build_from_data_dir() {
+        let source = Source::DataDir(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures"));
+        let gemojis = crate::github::build(&source, crate::github::VERSION).unwrap();
+        let emojis = build(&source, VERSION, &gemojis).unwrap();
+
+        let emoji: Vec<_> = emojis.iter().map(|e| e.entry.emoji.as_str()).collect();
+        assert_eq!(
+            emoji,
+            [
+                "😀",
+                "😄",
+                "☺️",
+                "👋",
+                "👋🏻",
+                "👋🏼",
+                "👋🏽",
+                "👋🏾",
+                "👋🏿",
+                "👩‍💻",
+                "🇩🇪"
+            ]
+        );
+
+        assert_eq!(emojis[0].entry.aliases, ["grinning"]);
+        assert_eq!(
+            emojis[0].entry.ios_version,
+            Some(Version { major: 6, minor: 0 })
+        );
+        assert_eq!(emojis[2].variations, ["☺"]);
+        assert_eq!(emojis[3].skin_tones, 6);
+        assert_eq!(emojis[3].skin_tone, Some(SkinTone::Default));
+        assert_eq!(emojis[8].skin_tone, Some(SkinTone::Dark));
+
+        let kinds: Vec<_> = emojis.iter().map(|e| e.entry.sequence_kind).collect();
+        assert_eq!(kinds[2], SequenceKind::Basic);
+        assert_eq!(kinds[4], SequenceKind::Modifier);
+        assert_eq!(kinds[9], SequenceKind::Zwj);
+        assert_eq!(kinds[10], SequenceKind::Flag);
+    }
+
+    #[test]
+    fn test_parse_sequences() {
+        let mut kinds = SequenceKinds::new();
+        parse_sequences(
+            "1F600..1F602 ; Basic_Emoji ; grinning face..face with tears of joy # E1.0 [3]\n\
+             0023 FE0F 20E3 ; Emoji_Keycap_Sequence ; keycap: # # E0.6 [1]\n",
+            &mut kinds,
+        )
+        .unwrap();
+        assert_eq!(kinds.get("😁"), Some(&SequenceKind::Basic));
+        assert_eq!(kinds.get("#\u{20e3}"), Some(&SequenceKind::Keycap));
+        assert_eq!(kinds.len(), 4);
+
+        assert!(parse_sequences("1F600 ; Emoji_Sequence ; x", &mut kinds).is_err());
+        assert!(parse_sequences("1F600..XYZ ; Basic_Emoji ; x", &mut kinds).is_err());
+    }
+
+    #[test]
+    fn test_unicode_url() {
+        assert_eq!(
+            unicode_url(VERSION, "emoji-test.txt"),
+            "https://unicode.org/Public/17.0.0/emoji/emoji-test.txt"
+        );
+        assert_eq!(
+            unicode_url("15.1".parse().unwrap(), "emoji-zwj-sequences.txt"),
+            "https://unicode.org/Public/emoji/15.1/emoji-zwj-sequences.txt"
+        );
+    }
+
+    #[test]
+    fn test_build_missing_data_dir() {
+        let source = Source::DataDir(Path::new(env!("CARGO_MANIFEST_DIR")).join("missing"));
+        assert!(build(&source, VERSION, &[]).is_err());
+    }
+}
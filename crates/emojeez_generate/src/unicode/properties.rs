@@ -0,0 +1,150 @@
+use std::error::Error;
+
+use crate::util::{Release, Source};
+
+use super::parse_code_point;
+
+/// Sorted, merged ranges of code points.
+pub type Ranges = Vec<(char, char)>;
+
+/// The code points of each emoji property in emoji-data.txt.
+///
+/// <https://www.unicode.org/reports/tr51/#Emoji_Properties>
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Properties {
+    pub emoji: Ranges,
+    pub emoji_presentation: Ranges,
+    pub emoji_modifier: Ranges,
+    pub emoji_modifier_base: Ranges,
+    pub emoji_component: Ranges,
+    pub extended_pictographic: Ranges,
+}
+
+/// emoji-data.txt moved from `Public/emoji/<major>.<minor>/` into the Unicode Character Database in 13.0.
+fn properties_url(version: Release) -> String {
+    if version.major >= 13 {
+        format!(
+            "https://unicode.org/Public/{}.{}.0/ucd/emoji/emoji-data.txt",
+            version.major, version.minor
+        )
+    } else {
+        format!(
+            "https://unicode.org/Public/emoji/{}.{}/emoji-data.txt",
+            version.major, version.minor
+        )
+    }
+}
+
+pub fn build(source: &Source, version: Release) -> Result<Properties, Box<dyn Error>> {
+    let data = source.read(&properties_url(version), "emoji-data.txt")?;
+    parse_properties(&data).map_err(|e| format!("Failed to parse emoji-data.txt: {e}").into())
+}
+
+fn parse_properties(data: &str) -> Result<Properties, Box<dyn Error>> {
+    let mut properties = Properties::default();
+    for line in data.lines() {
+        let line = line.split_once('#').map_or(line, |(data, _)| data).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (code_points, property) = line.split_once(';').ok_or("expected property")?;
+        let ranges = match property.trim() {
+            "Emoji" => &mut properties.emoji,
+            "Emoji_Presentation" => &mut properties.emoji_presentation,
+            "Emoji_Modifier" => &mut properties.emoji_modifier,
+            "Emoji_Modifier_Base" => &mut properties.emoji_modifier_base,
+            "Emoji_Component" => &mut properties.emoji_component,
+            "Extended_Pictographic" => &mut properties.extended_pictographic,
+            property => return Err(format!("unknown property: {property:?}").into()),
+        };
+        let code_points = code_points.trim();
+        ranges.push(match code_points.split_once("..") {
+            Some((first, last)) => (parse_code_point(first)?, parse_code_point(last)?),
+            None => (
+                parse_code_point(code_points)?,
+                parse_code_point(code_points)?,
+            ),
+        });
+    }
+
+    for ranges in [
+        &mut properties.emoji,
+        &mut properties.emoji_presentation,
+        &mut properties.emoji_modifier,
+        &mut properties.emoji_modifier_base,
+        &mut properties.emoji_component,
+        &mut properties.extended_pictographic,
+    ] {
+        merge(ranges);
+    }
+    Ok(properties)
+}
+
+/// Sorts `ranges` and joins the ones that touch or overlap.
+fn merge(ranges: &mut Ranges) {
+    ranges.sort_unstable();
+    let mut merged: Ranges = Vec::with_capacity(ranges.len());
+    for &(first, last) in ranges.iter() {
+        match merged.last_mut() {
+            Some((_, end)) if u32::from(first) <= u32::from(*end) + 1 => *end = (*end).max(last),
+            _ => merged.push((first, last)),
+        }
+    }
+    *ranges = merged;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::unicode::VERSION;
+
+    #[test]
+    fn test_build_from_data_dir() {
+        let source = Source::DataDir(Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures"));
+        let properties = build(&source, VERSION).unwrap();
+        assert_eq!(
+            properties.emoji,
+            [
+                ('#', '#'),
+                ('☺', '☺'),
+                ('\u{1f3fb}', '\u{1f3ff}'),
+                ('👋', '👋'),
+                ('😀', '😄')
+            ]
+        );
+        assert_eq!(
+            properties.emoji_presentation,
+            [('\u{1f3fb}', '\u{1f3ff}'), ('👋', '👋'), ('😀', '😄')]
+        );
+        assert_eq!(properties.emoji_modifier, [('\u{1f3fb}', '\u{1f3ff}')]);
+        assert_eq!(properties.emoji_modifier_base, [('👋', '👋')]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut ranges = vec![('c', 'd'), ('a', 'a'), ('b', 'b'), ('x', 'z'), ('y', 'y')];
+        merge(&mut ranges);
+        assert_eq!(ranges, [('a', 'd'), ('x', 'z')]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_properties("1F600 ; Emoji_Sequence").is_err());
+        assert!(parse_properties("1F600").is_err());
+        assert!(parse_properties("D800 ; Emoji").is_err());
+    }
+
+    #[test]
+    fn test_properties_url() {
+        assert_eq!(
+            properties_url(VERSION),
+            "https://unicode.org/Public/17.0.0/ucd/emoji/emoji-data.txt"
+        );
+        assert_eq!(
+            properties_url("12.1".parse().unwrap()),
+            "https://unicode.org/Public/emoji/12.1/emoji-data.txt"
+        );
+    }
+}
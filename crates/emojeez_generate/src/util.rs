@@ -1,9 +1,89 @@
-use std::{any::type_name, env, fs, io, path::Path};
+use std::{
+    any::type_name,
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
 
 use reqwest::blocking::Client;
 use sha2::{Digest, Sha256};
 
-pub fn cached_download(url: &str) -> Result<String, io::Error> {
+use crate::manifest;
+
+/// A `major.minor.patch` release of an input source, e.g. Unicode `17.0.0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Release {
+    pub major: usize,
+    pub minor: usize,
+    pub patch: usize,
+}
+
+impl fmt::Display for Release {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for Release {
+    type Err = String;
+
+    /// Parses `major[.minor[.patch]]`, missing parts being `0`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.').map(|part| {
+            part.parse()
+                .map_err(|_| format!("invalid version '{s}', expected major.minor.patch"))
+        });
+        let release = Self {
+            major: parts.next().unwrap_or(Ok(0))?,
+            minor: parts.next().unwrap_or(Ok(0))?,
+            patch: parts.next().unwrap_or(Ok(0))?,
+        };
+        if parts.next().is_some() {
+            return Err(format!("invalid version '{s}', expected major.minor.patch"));
+        }
+        Ok(release)
+    }
+}
+
+/// Where the generator reads its input files from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// Download each file, caching it in `target/generate`. With `refresh`, cached files are downloaded again. With
+    /// `allow_unpinned`, files without a pinned digest are used with a warning.
+    Download { refresh: bool, allow_unpinned: bool },
+    /// Read each file from a local directory, e.g. on air-gapped build machines, verified against the manifest of the
+    /// directory if it has one.
+    DataDir(PathBuf),
+}
+
+impl Source {
+    /// Reads the file published at `url`, which a data directory holds at the relative path `file_name`.
+    pub fn read(&self, url: &str, file_name: &str) -> Result<String, io::Error> {
+        match self {
+            Self::Download {
+                refresh,
+                allow_unpinned,
+            } => cached_download(url, *refresh, *allow_unpinned),
+            Self::DataDir(dir) => {
+                let path = dir.join(file_name);
+                eprintln!("using local: {}", path.display());
+                let data = fs::read_to_string(&path)
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+                manifest::verify_data_dir(dir, file_name, &data)?;
+                Ok(data)
+            }
+        }
+    }
+}
+
+/// Downloads `url`, or reads it from the cache unless `refresh` is set. Either way, the data is verified against its
+/// pinned digest, and only verified downloads are cached.
+pub fn cached_download(
+    url: &str,
+    refresh: bool,
+    allow_unpinned: bool,
+) -> Result<String, io::Error> {
     let checksum = hex::encode(Sha256::digest(url.as_bytes()));
     let path = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/target/generate"))
         .join(checksum)
@@ -12,11 +92,13 @@ pub fn cached_download(url: &str) -> Result<String, io::Error> {
     let cwd = env::current_dir()?;
 
     match fs::read_to_string(&path) {
+        Ok(_) if refresh => {}
         Ok(data) => {
             eprintln!(
                 "using cached: {url}\n    at {}",
                 path.strip_prefix(&cwd).unwrap_or(&path).display()
             );
+            manifest::verify(url, &data, allow_unpinned)?;
             return Ok(data);
         }
         Err(err) if err.kind() == io::ErrorKind::NotFound => {}
@@ -24,6 +106,7 @@ pub fn cached_download(url: &str) -> Result<String, io::Error> {
     }
 
     let data = download(url).map_err(|e| io::Error::other(format!("download failed: {e}")))?;
+    manifest::verify(url, &data, allow_unpinned)?;
 
     fs::create_dir_all(path.parent().unwrap())?;
     fs::write(&path, data.as_bytes())?;
@@ -58,6 +141,42 @@ pub fn download(url: &str) -> Result<String, io::Error> {
     Ok(text)
 }
 
+/// A path next to `path` to generate into, so that tools like `rustfmt` find the same configuration files.
+pub fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.tmp"))
+}
+
+/// Prints a unified diff from `old` to `new` with `diff -u`, treating a missing `old` as empty.
+pub fn diff(old: &Path, new: &Path) -> Result<(), io::Error> {
+    let status = Command::new("diff")
+        .args(["-u", "-N"])
+        .arg(old)
+        .arg(new)
+        .status()?;
+    // diff exits with 1 when the files differ.
+    if !matches!(status.code(), Some(0 | 1)) {
+        return Err(io::Error::other(format!(
+            "diff failed with status: {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Formats a generated Rust file in place, so that `cargo fmt --check` agrees with the generator.
+pub fn rustfmt(path: &Path) -> Result<(), io::Error> {
+    let status = Command::new("rustfmt")
+        .args(["--edition", "2024"])
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "rustfmt failed with status: {status}"
+        )));
+    }
+    Ok(())
+}
+
 pub fn struct_name<T>() -> String {
     let full = type_name::<T>();
     // Strip generic arguments first
@@ -74,6 +193,19 @@ pub fn struct_package<T>() -> &'static str {
     parts.next().unwrap_or("")
 }
 
+/// Formats an integer with `_` digit separators, as `clippy::unreadable_literal` expects.
+pub fn readable_literal(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push('_');
+        }
+        out.push(c);
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use unicode_types::Group;
@@ -93,4 +225,31 @@ mod tests {
         assert_eq!(struct_package::<i32>(), "");
         assert_eq!(struct_package::<Group>(), "unicode_types");
     }
+
+    #[test]
+    fn test_release() {
+        let release = |major, minor, patch| Release {
+            major,
+            minor,
+            patch,
+        };
+
+        assert_eq!("17.0.0".parse(), Ok(release(17, 0, 0)));
+        assert_eq!("15.1".parse(), Ok(release(15, 1, 0)));
+        assert_eq!("48".parse(), Ok(release(48, 0, 0)));
+        assert!("15.x".parse::<Release>().is_err());
+        assert!("1.2.3.4".parse::<Release>().is_err());
+        assert_eq!(release(4, 1, 0).to_string(), "4.1.0");
+        assert!(release(15, 1, 0) < release(17, 0, 0));
+    }
+
+    #[test]
+    fn test_readable_literal() {
+        assert_eq!(readable_literal(0), "0");
+        assert_eq!(readable_literal(12_345), "12_345");
+        assert_eq!(
+            readable_literal(16_287_231_350_648_472_473),
+            "16_287_231_350_648_472_473"
+        );
+    }
 }
@@ -0,0 +1,98 @@
+use crate::storage::{load_json, read_json, save_json};
+use std::error::Error;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The pinned emoji, kept in the user config directory as a JSON array of the emoji and the `:shortcode:` of custom
+/// emoji, in the order they were pinned.
+#[derive(Debug, Default)]
+pub struct Favorites {
+    path: PathBuf,
+    texts: Vec<String>,
+}
+
+impl Favorites {
+    /// Loads the favorites at `path`, starting empty if it does not exist or cannot be read.
+    pub fn load(path: PathBuf) -> Self {
+        let texts = load_json(&path).unwrap_or_default();
+        Self { path, texts }
+    }
+
+    pub fn texts(&self) -> &[String] {
+        &self.texts
+    }
+
+    pub fn contains(&self, text: &str) -> bool {
+        self.texts.iter().any(|favorite| favorite == text)
+    }
+
+    /// Pins `text`, or unpins it if it already is, and writes the favorites back to their file.
+    pub fn toggle(&mut self, text: &str) -> io::Result<()> {
+        match self.texts.iter().position(|favorite| favorite == text) {
+            Some(i) => {
+                self.texts.remove(i);
+            }
+            None => self.texts.push(text.to_owned()),
+        }
+        self.save()
+    }
+
+    /// Adds the favorites of an exported file after the current ones, returning how many were new.
+    pub fn import(&mut self, path: &Path) -> Result<usize, Box<dyn Error>> {
+        let before = self.texts.len();
+        for text in read_json::<Vec<String>>(path)? {
+            if !self.contains(&text) {
+                self.texts.push(text);
+            }
+        }
+        self.save()?;
+        Ok(self.texts.len() - before)
+    }
+
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        save_json(path, &self.texts)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        save_json(&self.path, &self.texts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_toggle_and_import() {
+        let dir = std::env::temp_dir().join(format!("emotif-favorites-{}", std::process::id()));
+        let mut favorites = Favorites::load(dir.join("favorites.json"));
+        assert!(favorites.texts().is_empty());
+
+        favorites.toggle("👍").unwrap();
+        favorites.toggle("🎉").unwrap();
+        favorites.toggle("👍").unwrap();
+        favorites.toggle("🚀").unwrap();
+        assert_eq!(favorites.texts(), ["🎉", "🚀"]);
+        assert_eq!(
+            Favorites::load(dir.join("favorites.json")).texts(),
+            ["🎉", "🚀"]
+        );
+
+        fs::write(dir.join("export.json"), r#"["🚀", ":parrot:", "👀"]"#).unwrap();
+        assert_eq!(favorites.import(&dir.join("export.json")).unwrap(), 2);
+        assert_eq!(favorites.texts(), ["🎉", "🚀", ":parrot:", "👀"]);
+
+        favorites.export(&dir.join("export.json")).unwrap();
+        assert_eq!(
+            read_json::<Vec<String>>(&dir.join("export.json")).unwrap(),
+            favorites.texts()
+        );
+
+        fs::write(dir.join("invalid.json"), "{}").unwrap();
+        assert!(favorites.import(&dir.join("invalid.json")).is_err());
+        assert_eq!(favorites.texts().len(), 4);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,12 +1,160 @@
-use emojeez::EMOJIS;
+mod favorites;
+mod recent;
+mod settings;
+mod storage;
+
+use emojeez::custom::{CustomEmoji, Registry};
+use emojeez::{EMOJIS, SkinToneExt, StaticEmoji, get, search};
+use favorites::Favorites;
 use gtk4::prelude::*;
 use gtk4::{
-    Application, ApplicationWindow, Box as GtkBox, Button, Grid, Label, Orientation,
-    ScrolledWindow, SearchEntry, gdk, glib,
+    Application, ApplicationWindow, Box as GtkBox, Button, CustomFilter, CustomSorter,
+    EventSequenceState, FileChooserAction, FileChooserNative, FileFilter, FilterChange,
+    FilterListModel, GestureClick, GestureLongPress, Grid, GridView, Image, Label, ListItem,
+    MenuButton, NoSelection, Orientation, Popover, ResponseType, ScrolledWindow, SearchEntry,
+    SignalListItemFactory, SortListModel, SorterChange, ToggleButton, gdk, gio, glib,
 };
+use recent::History;
+use settings::{Settings, first_tone};
 use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
 use std::rc::Rc;
-use unicode_types::Emoji;
+use storage::load_json;
+use unicode_types::{Group, SkinTone};
+
+/// Custom emoji, a JSON array of `{ shortcode, name, tags, image, category }` objects in the user config directory.
+const CUSTOM_EMOJI_FILE: &str = "emotif/custom-emoji.json";
+
+/// The recently used emoji with their use counts, in the user data directory.
+const RECENT_FILE: &str = "emotif/recent.json";
+
+/// The pinned emoji, a JSON array of emoji and `:shortcode:`s in the user config directory.
+const FAVORITES_FILE: &str = "emotif/favorites.json";
+
+/// The preferences of the picker, such as the skin tone, in the user config directory.
+const SETTINGS_FILE: &str = "emotif/settings.json";
+
+/// Two people holding hands, which has a variant for every [`SkinTone`] to choose the preferred one from.
+const SKIN_TONE_SAMPLE: &str = "🧑‍🤝‍🧑";
+
+/// How many skin tone variants a row of a chooser holds.
+const SKIN_TONE_COLUMNS: i32 = 6;
+
+/// How many emoji the Recent tab shows.
+const RECENT_LIMIT: usize = 48;
+
+/// The groups of the category bar, with the emoji shown on their button.
+const CATEGORIES: [(Group, &str); 9] = [
+    (Group::SmileysAndEmotion, "😀"),
+    (Group::PeopleAndBody, "👋"),
+    (Group::AnimalsAndNature, "🐶"),
+    (Group::FoodAndDrink, "🍔"),
+    (Group::TravelAndPlaces, "🚗"),
+    (Group::Activities, "⚽"),
+    (Group::Objects, "💡"),
+    (Group::Symbols, "❤️"),
+    (Group::Flags, "🏁"),
+];
+
+/// A tab of the category bar.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Recent,
+    Favorites,
+    Group(Group),
+    Custom,
+}
+
+impl Category {
+    const fn title(self) -> &'static str {
+        match self {
+            Self::Recent => "Recent",
+            Self::Favorites => "Favorites",
+            Self::Group(group) => group.as_str(),
+            Self::Custom => "Custom",
+        }
+    }
+}
+
+/// An emoji of the grid model, wrapped in a [`glib::BoxedAnyObject`].
+struct Item {
+    /// The position in the unfiltered model, which the grid keeps outside of searches.
+    index: usize,
+    category: Category,
+    /// What a click copies: the default-tone emoji, or the `:shortcode:` of a custom emoji.
+    text: String,
+    kind: ItemKind,
+}
+
+enum ItemKind {
+    Unicode(&'static StaticEmoji),
+    Custom(CustomEmoji),
+}
+
+/// What the grid shows: the emoji of a category, or the ranked matches of a search.
+struct View {
+    category: Category,
+    /// The rank of each matching [`Item::text`] while searching.
+    ranks: Option<HashMap<String, usize>>,
+    /// The rank of each [`Item::text`] of the Recent tab.
+    recent: HashMap<String, usize>,
+    /// The rank of each [`Item::text`] of the Favorites tab.
+    favorites: HashMap<String, usize>,
+}
+
+impl View {
+    /// The ranks that order the grid, if not the model order.
+    fn ranks(&self) -> Option<&HashMap<String, usize>> {
+        self.ranks.as_ref().or(match self.category {
+            Category::Recent => Some(&self.recent),
+            Category::Favorites => Some(&self.favorites),
+            Category::Group(_) | Category::Custom => None,
+        })
+    }
+
+    fn shows(&self, item: &Item) -> bool {
+        self.ranks()
+            .map_or(item.category == self.category, |ranks| {
+                ranks.contains_key(&item.text)
+            })
+    }
+
+    fn position(&self, item: &Item) -> usize {
+        self.ranks()
+            .and_then(|ranks| ranks.get(&item.text).copied())
+            .unwrap_or(item.index)
+    }
+}
+
+fn recent_ranks(history: &History) -> HashMap<String, usize> {
+    history
+        .ranked(RECENT_LIMIT)
+        .into_iter()
+        .enumerate()
+        .map(|(rank, text)| (text.to_owned(), rank))
+        .collect()
+}
+
+fn favorite_ranks(favorites: &Favorites) -> HashMap<String, usize> {
+    favorites
+        .texts()
+        .iter()
+        .enumerate()
+        .map(|(rank, text)| (text.clone(), rank))
+        .collect()
+}
+
+fn load_custom_emoji() -> Registry {
+    let path = glib::user_config_dir().join(CUSTOM_EMOJI_FILE);
+    let mut registry = Registry::new();
+    let emojis: Vec<CustomEmoji> = load_json(&path).unwrap_or_default();
+    for err in registry.extend(emojis) {
+        eprintln!("{}: {err}", path.display());
+    }
+    registry
+}
 
 fn build_ui(app: &Application) {
     let window = ApplicationWindow::builder()
@@ -24,119 +172,612 @@ fn build_ui(app: &Application) {
 
     let search_entry = SearchEntry::new();
     search_entry.set_placeholder_text(Some("Search emojis..."));
-    main_box.append(&search_entry);
+    search_entry.set_hexpand(true);
+
+    let top_bar = GtkBox::new(Orientation::Horizontal, 5);
+    top_bar.append(&search_entry);
+    main_box.append(&top_bar);
+
+    let custom = load_custom_emoji();
+    let store = emoji_store(&custom);
+
+    let history = History::load(glib::user_data_dir().join(RECENT_FILE));
+    let recent = recent_ranks(&history);
+    let history = Rc::new(RefCell::new(history));
+
+    let favorites = Favorites::load(glib::user_config_dir().join(FAVORITES_FILE));
+    let pinned = favorite_ranks(&favorites);
+    let favorites = Rc::new(RefCell::new(favorites));
+
+    let settings = Rc::new(RefCell::new(Settings::load(
+        &glib::user_config_dir().join(SETTINGS_FILE),
+    )));
+
+    // Start from the Recent tab once something has been used
+    let view = Rc::new(RefCell::new(View {
+        category: if recent.is_empty() {
+            Category::Group(CATEGORIES[0].0)
+        } else {
+            Category::Recent
+        },
+        ranks: None,
+        recent,
+        favorites: pinned,
+    }));
+
+    // Searching and switching categories only filter and sort the model; the grid keeps its widgets
+    let filter = CustomFilter::new({
+        let view = view.clone();
+        move |object| view.borrow().shows(&item(object))
+    });
+    let sorter = CustomSorter::new({
+        let view = view.clone();
+        move |a, b| {
+            let view = view.borrow();
+            view.position(&item(a)).cmp(&view.position(&item(b))).into()
+        }
+    });
+    let filtered = FilterListModel::new(Some(store), Some(filter.clone()));
+    let sorted = SortListModel::new(Some(filtered), Some(sorter.clone()));
+    let selection = NoSelection::new(Some(sorted.clone()));
+
+    let grid_view = GridView::new(Some(selection), None::<SignalListItemFactory>);
+    grid_view.set_max_columns(8);
+    grid_view.set_single_click_activate(true);
+
+    // Copies an emoji, or one of its skin tone variants, counting a use of the grid item
+    let copy = {
+        let clipboard = window.clipboard();
+        let history = history.clone();
+        let view = view.clone();
+        move |text: &str, copied: &str| {
+            clipboard.set_text(copied);
+
+            // The Recent tab picks this up the next time it is selected, not under the pointer
+            let mut history = history.borrow_mut();
+            if let Err(err) = history.record(text) {
+                eprintln!(
+                    "{}: {err}",
+                    glib::user_data_dir().join(RECENT_FILE).display()
+                );
+            }
+            view.borrow_mut().recent = recent_ranks(&history);
+        }
+    };
+    grid_view.connect_activate({
+        let settings = settings.clone();
+        let copy = copy.clone();
+        move |_, position| {
+            let Some(object) = sorted.item(position) else {
+                return;
+            };
+            let text = &item(&object).text;
+            copy(text, &in_skin_tone(text, settings.borrow().skin_tone));
+        }
+    });
 
     // Scrolled window for emoji grid
     let scrolled_window = ScrolledWindow::new();
     scrolled_window.set_vexpand(true);
+    scrolled_window.set_child(Some(&grid_view));
 
-    let grid = Grid::new();
-    grid.set_row_spacing(5);
-    grid.set_column_spacing(5);
-    grid.set_margin_top(10);
-
-    scrolled_window.set_child(Some(&grid));
-    main_box.append(&scrolled_window);
+    let header = Label::new(Some(view.borrow().category.title()));
+    header.set_halign(gtk4::Align::Start);
+    header.set_css_classes(&["heading"]);
 
-    let clipboard = window.clipboard();
+    let refresh = {
+        let view = view.clone();
+        let header = header.clone();
+        let scrolled_window = scrolled_window.clone();
+        move || {
+            let view = view.borrow();
+            header.set_label(if view.ranks.is_some() {
+                "Search results"
+            } else {
+                view.category.title()
+            });
+            filter.changed(FilterChange::Different);
+            sorter.changed(SorterChange::Different);
+            scrolled_window.vadjustment().set_value(0.0);
+        }
+    };
 
-    let button_cache: Rc<RefCell<Vec<Button>>> = Rc::new(RefCell::new(Vec::new()));
+    // Pins or unpins an emoji, updating the Favorites tab if it is showing
+    let toggle_favorite = {
+        let favorites = favorites.clone();
+        let view = view.clone();
+        let refresh = refresh.clone();
+        move |text: &str| {
+            let mut favorites = favorites.borrow_mut();
+            if let Err(err) = favorites.toggle(text) {
+                eprintln!(
+                    "{}: {err}",
+                    glib::user_config_dir().join(FAVORITES_FILE).display()
+                );
+            }
+            update_favorites(&favorites, &view, &refresh);
+        }
+    };
+    grid_view.set_factory(Some(&emoji_factory({
+        let favorites = favorites.clone();
+        move |widget, text| {
+            let pinned = favorites.borrow().contains(text);
+            context_menu(widget, text, pinned, toggle_favorite.clone(), copy.clone());
+        }
+    })));
+    top_bar.append(&skin_tone_button(&settings));
+    top_bar.append(&favorites_menu_button(&window, &favorites, &view, &refresh));
 
-    populate_grid(&grid, EMOJIS, "", &clipboard, &button_cache);
+    let tabs: Vec<_> = [(Category::Recent, "🕘"), (Category::Favorites, "⭐")]
+        .into_iter()
+        .chain(
+            CATEGORIES
+                .iter()
+                .map(|&(group, icon)| (Category::Group(group), icon)),
+        )
+        .chain((!custom.is_empty()).then_some((Category::Custom, "🧩")))
+        .collect();
+    let active = view.borrow().category;
+    let category_bar = category_bar(&tabs, active, {
+        let view = view.clone();
+        let refresh = refresh.clone();
+        move |category| {
+            view.borrow_mut().category = category;
+            refresh();
+        }
+    });
+    main_box.append(&category_bar);
+    main_box.append(&header);
+    main_box.append(&scrolled_window);
 
+    let custom = Rc::new(custom);
     let search_generation: Rc<RefCell<u32>> = Rc::new(RefCell::new(0));
 
-    search_entry.connect_search_changed({
-        let grid = grid.clone();
-        let clipboard = clipboard.clone();
-        let button_cache = button_cache.clone();
-        let search_generation = search_generation.clone();
-
-        move |entry| {
-            *search_generation.borrow_mut() += 1;
-            let current_gen = *search_generation.borrow();
-
-            let query = entry.text().to_string();
-            let grid = grid.clone();
-            let clipboard = clipboard.clone();
-            let button_cache = button_cache.clone();
-            let search_generation_inner = search_generation.clone();
-
-            glib::timeout_add_local_once(std::time::Duration::from_millis(300), move || {
-                if *search_generation_inner.borrow() == current_gen {
-                    populate_grid(&grid, EMOJIS, &query, &clipboard, &button_cache);
+    search_entry.connect_search_changed(move |entry| {
+        *search_generation.borrow_mut() += 1;
+        let current_gen = *search_generation.borrow();
+
+        let query = entry.text().trim().to_owned();
+        let custom = custom.clone();
+        let history = history.clone();
+        let view = view.clone();
+        let refresh = refresh.clone();
+        let category_bar = category_bar.clone();
+        let search_generation_inner = search_generation.clone();
+
+        glib::timeout_add_local_once(std::time::Duration::from_millis(300), move || {
+            if *search_generation_inner.borrow() != current_gen {
+                return;
+            }
+            // Ranked search results, best match first, boosted by how often and recently they were used
+            view.borrow_mut().ranks = (!query.is_empty()).then(|| {
+                let history = history.borrow();
+                // The grid only holds default-tone emoji, standing in for their toned matches
+                let unicode = search(&query)
+                    .into_iter()
+                    .map(|m| (m.emoji.base().entry.emoji.to_owned(), m.score));
+                let custom = custom
+                    .search(&query)
+                    .into_iter()
+                    .map(|m| (format!(":{}:", m.emoji.shortcode), m.score));
+                let mut matches: Vec<_> = unicode
+                    .chain(custom)
+                    .map(|(text, score)| {
+                        let score = score + history.search_boost(&text);
+                        (text, score)
+                    })
+                    .collect();
+                matches.sort_by_key(|&(_, score)| Reverse(score));
+                let mut ranks = HashMap::new();
+                for (rank, (text, _)) in matches.into_iter().enumerate() {
+                    ranks.entry(text).or_insert(rank);
                 }
+                ranks
             });
-        }
+            category_bar.set_sensitive(query.is_empty());
+            refresh();
+        });
     });
 
     window.set_child(Some(&main_box));
     window.present();
 }
 
-fn populate_grid(
-    grid: &Grid,
-    emojis: &[Emoji<&'static str, &'static [&'static str]>],
-    query: &str,
-    clipboard: &gdk::Clipboard,
-    button_cache: &Rc<RefCell<Vec<Button>>>,
-) {
-    // Remove all children from grid
-    while let Some(child) = grid.first_child() {
-        grid.remove(&child);
+fn update_favorites(favorites: &Favorites, view: &RefCell<View>, refresh: &impl Fn()) {
+    let showing = {
+        let mut view = view.borrow_mut();
+        view.favorites = favorite_ranks(favorites);
+        view.category == Category::Favorites && view.ranks.is_none()
+    };
+    if showing {
+        refresh();
     }
+}
 
-    let columns = 8;
-    let query_lower = query.to_lowercase();
+/// The context menu of a grid cell, with the skin tone variants of its emoji to copy, and pinning or unpinning it.
+fn context_menu(
+    cell: &gtk4::Widget,
+    text: &str,
+    pinned: bool,
+    toggle_favorite: impl Fn(&str) + 'static,
+    copy: impl Fn(&str, &str) + Clone + 'static,
+) {
+    let popover = Popover::new();
+    let menu = GtkBox::new(Orientation::Vertical, 5);
+
+    let variants = get(text).map_or(&[][..], SkinToneExt::skin_tone_variants);
+    if variants.len() > 1 {
+        let text = text.to_owned();
+        let weak_popover = popover.downgrade();
+        menu.append(&skin_tone_grid(variants, move |variant| {
+            weak_popover.upgrade().inspect(Popover::popdown);
+            copy(&text, variant.entry.emoji);
+        }));
+    }
 
-    // Pre-filter emojis to avoid unnecessary iterations
-    let filtered_emojis: Vec<_> = if query.is_empty() {
-        emojis.iter().collect()
+    let button = Button::with_label(if pinned {
+        "Remove from Favorites"
     } else {
-        emojis
-            .iter()
-            .filter(|emoji| emoji.matches_search(&query_lower))
-            .collect()
-    };
+        "Add to Favorites"
+    });
+    button.set_has_frame(false);
+    menu.append(&button);
+
+    popover.set_child(Some(&menu));
+    popover.set_parent(cell);
+    popover.connect_closed(|popover| popover.unparent());
 
-    let mut cache = button_cache.borrow_mut();
+    let weak_popover = popover.downgrade();
+    let text = text.to_owned();
+    button.connect_clicked(move |_| {
+        weak_popover.upgrade().inspect(Popover::popdown);
+        toggle_favorite(&text);
+    });
+    popover.popup();
+}
 
-    // Ensure we have enough buttons in cache
-    while cache.len() < filtered_emojis.len() {
+/// A grid of buttons, one per skin tone variant.
+fn skin_tone_grid(
+    variants: &'static [StaticEmoji],
+    on_click: impl Fn(&'static StaticEmoji) + Clone + 'static,
+) -> Grid {
+    let grid = Grid::new();
+    for (i, variant) in (0..).zip(variants) {
+        let label = Label::new(None);
+        label.set_markup(&format!(
+            "<span font_desc='24'>{}</span>",
+            variant.entry.emoji
+        ));
         let button = Button::new();
-        let emoji_label = Label::new(None);
-        emoji_label.set_css_classes(&["emoji-label"]);
-        button.set_child(Some(&emoji_label));
-        cache.push(button);
+        button.set_child(Some(&label));
+        button.set_has_frame(false);
+        button.set_tooltip_text(Some(variant.entry.name));
+
+        let on_click = on_click.clone();
+        button.connect_clicked(move |_| on_click(variant));
+        grid.attach(&button, i % SKIN_TONE_COLUMNS, i / SKIN_TONE_COLUMNS, 1, 1);
     }
+    grid
+}
+
+/// `text` in `skin_tone`, or in the tone of the first person for emoji without two-tone variants, or as it is.
+fn in_skin_tone(text: &str, skin_tone: SkinTone) -> String {
+    get(text).map_or_else(
+        || text.to_owned(),
+        |emoji| {
+            emoji
+                .with_skin_tone(skin_tone)
+                .or_else(|| emoji.with_skin_tone(first_tone(skin_tone)))
+                .unwrap_or(emoji)
+                .entry
+                .emoji
+                .to_owned()
+        },
+    )
+}
+
+/// A menu to choose the preferred skin tone, showing it on the button.
+fn skin_tone_button(settings: &Rc<RefCell<Settings>>) -> MenuButton {
+    let button = MenuButton::new();
+    button.set_tooltip_text(Some("Skin tone"));
+    button.set_label(&in_skin_tone(SKIN_TONE_SAMPLE, settings.borrow().skin_tone));
+
+    let popover = Popover::new();
+    if let Some(sample) = get(SKIN_TONE_SAMPLE) {
+        let settings = settings.clone();
+        let weak_button = button.downgrade();
+        let weak_popover = popover.downgrade();
+        popover.set_child(Some(&skin_tone_grid(
+            sample.skin_tone_variants(),
+            move |variant| {
+                weak_popover.upgrade().inspect(Popover::popdown);
+                if let Some(button) = weak_button.upgrade() {
+                    button.set_label(variant.entry.emoji);
+                }
+
+                let mut settings = settings.borrow_mut();
+                settings.skin_tone = variant.skin_tone.unwrap_or(SkinTone::Default);
+                let path = glib::user_config_dir().join(SETTINGS_FILE);
+                if let Err(err) = settings.save(&path) {
+                    eprintln!("{}: {err}", path.display());
+                }
+            },
+        )));
+    }
+    button.set_popover(Some(&popover));
+    button
+}
+
+/// A menu to import favorites from and export them to a JSON file.
+fn favorites_menu_button(
+    window: &ApplicationWindow,
+    favorites: &Rc<RefCell<Favorites>>,
+    view: &Rc<RefCell<View>>,
+    refresh: &(impl Fn() + Clone + 'static),
+) -> MenuButton {
+    let import = Button::with_label("Import Favorites…");
+    let export = Button::with_label("Export Favorites…");
+    let menu = GtkBox::new(Orientation::Vertical, 0);
+    for button in [&import, &export] {
+        button.set_has_frame(false);
+        menu.append(button);
+    }
+    let popover = Popover::new();
+    popover.set_child(Some(&menu));
+
+    import.connect_clicked({
+        let window = window.clone();
+        let favorites = favorites.clone();
+        let view = view.clone();
+        let refresh = refresh.clone();
+        let popover = popover.downgrade();
+        move |_| {
+            popover.upgrade().inspect(Popover::popdown);
+            let favorites = favorites.clone();
+            let view = view.clone();
+            let refresh = refresh.clone();
+            choose_json_file(
+                &window,
+                "Import Favorites",
+                FileChooserAction::Open,
+                move |path| {
+                    let mut favorites = favorites.borrow_mut();
+                    if let Err(err) = favorites.import(path) {
+                        eprintln!("{}: {err}", path.display());
+                    }
+                    update_favorites(&favorites, &view, &refresh);
+                },
+            );
+        }
+    });
+    export.connect_clicked({
+        let window = window.clone();
+        let favorites = favorites.clone();
+        let popover = popover.downgrade();
+        move |_| {
+            popover.upgrade().inspect(Popover::popdown);
+            let favorites = favorites.clone();
+            choose_json_file(
+                &window,
+                "Export Favorites",
+                FileChooserAction::Save,
+                move |path| {
+                    if let Err(err) = favorites.borrow().export(path) {
+                        eprintln!("{}: {err}", path.display());
+                    }
+                },
+            );
+        }
+    });
 
-    // Reuse existing buttons
-    for (idx, emoji) in filtered_emojis.iter().enumerate() {
-        let button = &cache[idx];
+    let button = MenuButton::new();
+    button.set_icon_name("open-menu-symbolic");
+    button.set_tooltip_text(Some("Favorites"));
+    button.set_popover(Some(&popover));
+    button
+}
+
+/// Asks for a JSON file to open or save, calling `on_file` once one is chosen.
+fn choose_json_file(
+    window: &ApplicationWindow,
+    title: &str,
+    action: FileChooserAction,
+    on_file: impl Fn(&Path) + 'static,
+) {
+    let dialog = FileChooserNative::new(Some(title), Some(window), action, None, None);
+    let filter = FileFilter::new();
+    filter.set_name(Some("JSON"));
+    filter.add_pattern("*.json");
+    dialog.add_filter(&filter);
+    if action == FileChooserAction::Save {
+        dialog.set_current_name("favorites.json");
+    }
 
-        // Update label
-        if let Some(child) = button.child()
-            && let Ok(label) = child.downcast::<Label>()
+    // Nothing else holds the dialog, so its own handler keeps it alive until it is answered
+    let keep_alive = RefCell::new(Some(dialog.clone()));
+    dialog.connect_response(move |dialog, response| {
+        keep_alive.take();
+        if response == ResponseType::Accept
+            && let Some(path) = dialog.file().and_then(|file| file.path())
         {
-            let markup = format!("<span font_desc='32'>{}</span>", emoji.entry.emoji);
-            label.set_markup(&markup);
+            on_file(&path);
         }
+    });
+    dialog.show();
+}
+
+/// Every default-tone Unicode emoji and then every custom emoji, in picker order.
+fn emoji_store(custom: &Registry) -> gio::ListStore {
+    let base = EMOJIS
+        .iter()
+        .filter(|emoji| emoji.skin_tone.is_none_or(|tone| tone == SkinTone::Default));
+    let unicode = base.map(|emoji| {
+        (
+            Category::Group(emoji.entry.group),
+            emoji.entry.emoji.to_owned(),
+            ItemKind::Unicode(emoji),
+        )
+    });
+    let custom = custom.emojis().iter().map(|emoji| {
+        (
+            Category::Custom,
+            format!(":{}:", emoji.shortcode),
+            ItemKind::Custom(emoji.clone()),
+        )
+    });
+
+    let store = gio::ListStore::new::<glib::BoxedAnyObject>();
+    let items: Vec<_> = unicode
+        .chain(custom)
+        .enumerate()
+        .map(|(index, (category, text, kind))| {
+            glib::BoxedAnyObject::new(Item {
+                index,
+                category,
+                text,
+                kind,
+            })
+        })
+        .collect();
+    store.extend_from_slice(&items);
+    store
+}
 
-        button.set_tooltip_text(Some(emoji.entry.name));
+fn item(object: &glib::Object) -> std::cell::Ref<'_, Item> {
+    object
+        .downcast_ref::<glib::BoxedAnyObject>()
+        .expect("the grid model holds BoxedAnyObject")
+        .borrow()
+}
 
-        // Disconnect previous signal handlers and connect new one
-        let emoji_text = emoji.entry.emoji.to_string();
-        let clipboard_clone = clipboard.clone();
+/// Grid cells showing the emoji, or the image of a custom emoji, calling `on_context_menu` on a secondary click or a
+/// long press.
+///
+/// Only local images are loaded; custom emoji with an image URL show their shortcode instead.
+fn emoji_factory(
+    on_context_menu: impl Fn(&gtk4::Widget, &str) + Clone + 'static,
+) -> SignalListItemFactory {
+    let factory = SignalListItemFactory::new();
+    factory.connect_setup(move |_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<ListItem>()
+            .expect("the factory builds ListItems");
 
-        // Store signal handler id to avoid memory leaks
-        button.connect_clicked(move |_| {
-            clipboard_clone.set_text(&emoji_text);
+        let cell = GtkBox::new(Orientation::Vertical, 0);
+        cell.append(&Label::new(None));
+        let image = Image::new();
+        image.set_pixel_size(40);
+        cell.append(&image);
+
+        // Cells are reused as the grid scrolls, so the menu is for the item shown when pressed
+        let show_menu = {
+            let list_item = list_item.downgrade();
+            let on_context_menu = on_context_menu.clone();
+            move |cell: &gtk4::Widget| {
+                if let Some(object) = list_item.upgrade().and_then(|list_item| list_item.item()) {
+                    let text = item(&object).text.clone();
+                    on_context_menu(cell, &text);
+                }
+            }
+        };
+
+        let context_click = GestureClick::new();
+        context_click.set_button(gdk::BUTTON_SECONDARY);
+        context_click.connect_pressed({
+            let show_menu = show_menu.clone();
+            move |gesture, _, _, _| {
+                if let Some(cell) = gesture.widget() {
+                    show_menu(&cell);
+                }
+            }
         });
+        cell.add_controller(context_click);
+
+        let long_press = GestureLongPress::new();
+        long_press.connect_pressed(move |gesture, _, _| {
+            // Keep the grid from also activating the cell on release
+            gesture.set_state(EventSequenceState::Claimed);
+            if let Some(cell) = gesture.widget() {
+                show_menu(&cell);
+            }
+        });
+        cell.add_controller(long_press);
+
+        list_item.set_child(Some(&cell));
+    });
+    factory.connect_bind(|_, list_item| {
+        let list_item = list_item
+            .downcast_ref::<ListItem>()
+            .expect("the factory builds ListItems");
+        let (Some(object), Some(cell)) = (list_item.item(), list_item.child()) else {
+            return;
+        };
+        let item = item(&object);
+        let label = cell
+            .first_child()
+            .and_downcast::<Label>()
+            .expect("cells start with a label");
+        let image = cell
+            .last_child()
+            .and_downcast::<Image>()
+            .expect("cells end with an image");
 
-        let row = (idx / columns) as i32;
-        let col = (idx % columns) as i32;
-        grid.attach(button, col, row, 1, 1);
+        let (tooltip, image_path) = match &item.kind {
+            ItemKind::Unicode(emoji) => {
+                label.set_css_classes(&["emoji-label"]);
+                label.set_markup(&format!(
+                    "<span font_desc='32'>{}</span>",
+                    emoji.entry.emoji
+                ));
+                (emoji.entry.name, None)
+            }
+            ItemKind::Custom(emoji) if Path::new(&emoji.image).is_file() => {
+                (emoji.name.as_str(), Some(&emoji.image))
+            }
+            ItemKind::Custom(emoji) => {
+                label.set_css_classes(&[]);
+                label.set_text(&item.text);
+                (emoji.name.as_str(), None)
+            }
+        };
+        match image_path {
+            Some(path) => image.set_from_file(Some(path)),
+            None => image.clear(),
+        }
+        label.set_visible(image_path.is_none());
+        image.set_visible(image_path.is_some());
+        cell.set_tooltip_text(Some(tooltip));
+    });
+    factory
+}
+
+/// A row of tabs with the emoji of each category on them, `active` selected.
+fn category_bar(
+    tabs: &[(Category, &str)],
+    active: Category,
+    on_select: impl Fn(Category) + Clone + 'static,
+) -> GtkBox {
+    let bar = GtkBox::new(Orientation::Horizontal, 5);
+    bar.set_homogeneous(true);
+
+    let mut first: Option<ToggleButton> = None;
+    for &(category, icon) in tabs {
+        let button = ToggleButton::with_label(icon);
+        button.set_tooltip_text(Some(category.title()));
+        button.set_has_frame(false);
+        button.set_group(first.as_ref());
+        button.set_active(category == active);
+        first.get_or_insert_with(|| button.clone());
+
+        let on_select = on_select.clone();
+        button.connect_toggled(move |button| {
+            if button.is_active() {
+                on_select(category);
+            }
+        });
+        bar.append(&button);
     }
+    bar
 }
 
 fn main() -> glib::ExitCode {
@@ -0,0 +1,158 @@
+use crate::storage::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many emoji the history remembers, dropping the least used ones first.
+const MAX_ENTRIES: usize = 200;
+
+/// A use counts half as much for every week since the emoji was last used.
+const HALF_LIFE: u64 = 7 * 24 * 60 * 60;
+
+/// The search score added per point of frecency, so often used emoji win between similar matches.
+const SEARCH_BOOST: u32 = 20;
+const MAX_SEARCH_BOOST: u32 = 200;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    /// The copied text, the emoji or the `:shortcode:` of a custom emoji.
+    text: String,
+    count: u32,
+    /// Seconds since the Unix epoch.
+    last_used: u64,
+}
+
+impl Entry {
+    /// The use count, decayed by the time since the last use.
+    fn frecency(&self, now: u64) -> u32 {
+        let half_lives = now.saturating_sub(self.last_used) / HALF_LIFE;
+        self.count
+            .checked_shr(u32::try_from(half_lives).unwrap_or(u32::MAX))
+            .unwrap_or(0)
+    }
+}
+
+/// The recently and frequently used emoji, kept as JSON in the user data directory.
+#[derive(Debug, Default)]
+pub struct History {
+    path: PathBuf,
+    /// Most recently used first.
+    entries: Vec<Entry>,
+}
+
+impl History {
+    /// Loads the history at `path`, starting empty if it does not exist or cannot be read.
+    pub fn load(path: PathBuf) -> Self {
+        let entries = load_json(&path).unwrap_or_default();
+        Self { path, entries }
+    }
+
+    /// Counts a use of `text` and writes the history back to its file.
+    pub fn record(&mut self, text: &str) -> io::Result<()> {
+        self.record_at(text, now());
+        self.save()
+    }
+
+    fn record_at(&mut self, text: &str, now: u64) {
+        let mut entry = match self.entries.iter().position(|entry| entry.text == text) {
+            Some(i) => self.entries.remove(i),
+            None => Entry {
+                text: text.to_owned(),
+                count: 0,
+                last_used: 0,
+            },
+        };
+        entry.count = entry.count.saturating_add(1);
+        entry.last_used = now;
+        self.entries.insert(0, entry);
+
+        // Oldest first, so ties drop the longest unused emoji
+        if self.entries.len() > MAX_ENTRIES {
+            let least_used = self
+                .entries
+                .iter()
+                .enumerate()
+                .rev()
+                .min_by_key(|(_, entry)| (entry.frecency(now), entry.last_used))
+                .map(|(i, _)| i);
+            if let Some(i) = least_used {
+                self.entries.remove(i);
+            }
+        }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        save_json(&self.path, &self.entries)
+    }
+
+    /// Up to `limit` emoji, most frecent first, the most recent breaking ties.
+    pub fn ranked(&self, limit: usize) -> Vec<&str> {
+        self.ranked_at(limit, now())
+    }
+
+    fn ranked_at(&self, limit: usize, now: u64) -> Vec<&str> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        // Stable, so equal frecencies stay most recent first
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.frecency(now)));
+        entries
+            .into_iter()
+            .take(limit)
+            .map(|entry| entry.text.as_str())
+            .collect()
+    }
+
+    /// What to add to the search score of `text` for having been used.
+    pub fn search_boost(&self, text: &str) -> u32 {
+        let now = now();
+        self.entries
+            .iter()
+            .find(|entry| entry.text == text)
+            .map_or(0, |entry| {
+                entry
+                    .frecency(now)
+                    .saturating_mul(SEARCH_BOOST)
+                    .min(MAX_SEARCH_BOOST)
+            })
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranked() {
+        let mut history = History::default();
+        history.record_at("😀", 0);
+        history.record_at("😀", 0);
+        history.record_at("👋", 1);
+        history.record_at("🎉", 2);
+        assert_eq!(history.ranked_at(10, 2), ["😀", "🎉", "👋"]);
+        assert_eq!(history.ranked_at(1, 2), ["😀"]);
+
+        // Two weeks later the old uses have decayed, leaving the most recent first
+        let later = 2 * HALF_LIFE;
+        history.record_at("🎉", later);
+        assert_eq!(history.ranked_at(10, later), ["🎉", "👋", "😀"]);
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let mut history = History::default();
+        history.record_at("keep", 0);
+        history.record_at("keep", 0);
+        for i in 0..MAX_ENTRIES {
+            history.record_at(&i.to_string(), 1);
+        }
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert!(history.ranked_at(MAX_ENTRIES, 1).contains(&"keep"));
+        assert!(!history.ranked_at(MAX_ENTRIES, 1).contains(&"0"));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::storage::{load_json, save_json};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::Path;
+use unicode_types::SkinTone;
+
+/// The preferences of the picker, kept as JSON in the user config directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The skin tone a click copies emoji in, where they have it.
+    pub skin_tone: SkinTone,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            skin_tone: SkinTone::Default,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings at `path`, using the defaults if it does not exist or cannot be read.
+    pub fn load(path: &Path) -> Self {
+        load_json(path).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        save_json(path, self)
+    }
+}
+
+/// The tone of the first person of a two-tone skin tone, e.g. [`SkinTone::Light`] for [`SkinTone::LightAndDark`].
+pub const fn first_tone(skin_tone: SkinTone) -> SkinTone {
+    match skin_tone {
+        SkinTone::LightAndMediumLight
+        | SkinTone::LightAndMedium
+        | SkinTone::LightAndMediumDark
+        | SkinTone::LightAndDark => SkinTone::Light,
+        SkinTone::MediumLightAndLight
+        | SkinTone::MediumLightAndMedium
+        | SkinTone::MediumLightAndMediumDark
+        | SkinTone::MediumLightAndDark => SkinTone::MediumLight,
+        SkinTone::MediumAndLight
+        | SkinTone::MediumAndMediumLight
+        | SkinTone::MediumAndMediumDark
+        | SkinTone::MediumAndDark => SkinTone::Medium,
+        SkinTone::MediumDarkAndLight
+        | SkinTone::MediumDarkAndMediumLight
+        | SkinTone::MediumDarkAndMedium
+        | SkinTone::MediumDarkAndDark => SkinTone::MediumDark,
+        SkinTone::DarkAndLight
+        | SkinTone::DarkAndMediumLight
+        | SkinTone::DarkAndMedium
+        | SkinTone::DarkAndMediumDark => SkinTone::Dark,
+        tone => tone,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_json() {
+        let settings: Settings = serde_json::from_str(r#"{"skin_tone":"MediumDark"}"#).unwrap();
+        assert_eq!(settings.skin_tone, SkinTone::MediumDark);
+        assert_eq!(
+            serde_json::from_str::<Settings>("{}").unwrap(),
+            Settings::default()
+        );
+    }
+
+    #[test]
+    fn test_first_tone() {
+        assert_eq!(first_tone(SkinTone::LightAndDark), SkinTone::Light);
+        assert_eq!(first_tone(SkinTone::DarkAndMediumDark), SkinTone::Dark);
+        assert_eq!(first_tone(SkinTone::Medium), SkinTone::Medium);
+        assert_eq!(first_tone(SkinTone::Default), SkinTone::Default);
+    }
+}
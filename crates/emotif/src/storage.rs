@@ -0,0 +1,56 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::error::Error;
+use std::path::Path;
+use std::{fs, io};
+
+/// Reads the JSON file at `path`, or `None` if it does not exist. Other errors are printed, also giving `None`, so a
+/// broken file falls back to the defaults rather than stopping the picker.
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json)
+            .map_err(|err| eprintln!("{}: {err}", path.display()))
+            .ok(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => {
+            eprintln!("{}: {err}", path.display());
+            None
+        }
+    }
+}
+
+/// Reads the JSON file at `path`, for files the user picked, whose errors are theirs to see.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, Box<dyn Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Writes `value` as JSON to `path`, creating its directory if needed.
+pub fn save_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_and_save() {
+        let dir = std::env::temp_dir().join(format!("emotif-storage-{}", std::process::id()));
+        let path = dir.join("nested").join("values.json");
+        assert_eq!(load_json::<Vec<u32>>(&path), None);
+
+        save_json(&path, &[1, 2, 3]).unwrap();
+        assert_eq!(load_json::<Vec<u32>>(&path), Some(vec![1, 2, 3]));
+        assert_eq!(read_json::<Vec<u32>>(&path).unwrap(), [1, 2, 3]);
+
+        fs::write(&path, "{}").unwrap();
+        assert_eq!(load_json::<Vec<u32>>(&path), None);
+        assert!(read_json::<Vec<u32>>(&path).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Entry<S, C>
 where
@@ -11,6 +11,9 @@ where
     pub group: Group,
     pub subgroup: S,
     pub status: Status,
+    /// Missing in JSON written before sequence kinds were added, which then reads as [`SequenceKind::Basic`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sequence_kind: SequenceKind,
     pub unicode_version: Version,
     pub emoji: S,
     pub name: S,
@@ -26,14 +29,43 @@ pub struct Version {
     pub minor: i16,
 }
 
+/// Deserializes from `"major.minor"`, as in gemoji, or from the `{ "major": .., "minor": .. }` map it serializes to.
 #[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Version {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
-        D: serde::Deserializer<'de>,
+        D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Self::try_from(s.as_str()).map_err(serde::de::Error::custom)
+        struct VersionVisitor;
+
+        impl<'de> de::Visitor<'de> for VersionVisitor {
+            type Value = Version;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a \"major.minor\" string or a map with major and minor")
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Version, E> {
+                Version::try_from(s).map_err(E::custom)
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Version, A::Error> {
+                let (mut major, mut minor) = (None, None);
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "major" => major = Some(map.next_value()?),
+                        "minor" => minor = Some(map.next_value()?),
+                        _ => return Err(de::Error::unknown_field(&key, &["major", "minor"])),
+                    }
+                }
+                Ok(Version {
+                    major: major.ok_or_else(|| de::Error::missing_field("major"))?,
+                    minor: minor.ok_or_else(|| de::Error::missing_field("minor"))?,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(VersionVisitor)
     }
 }
 
@@ -41,7 +73,9 @@ impl TryFrom<&str> for Version {
     type Error = String;
 
     fn try_from(s: &str) -> Result<Self, Self::Error> {
-        let (major_str, minor_str) = s.split_once('.').unwrap();
+        let (major_str, minor_str) = s
+            .split_once('.')
+            .ok_or_else(|| format!("Invalid version: {s}"))?;
 
         let major = major_str
             .parse::<i16>()
@@ -63,7 +97,7 @@ impl TryFrom<String> for Version {
     }
 }
 
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Emoji<S, C>
 where
@@ -76,7 +110,88 @@ where
     pub variations: C,
 }
 
-#[cfg_attr(feature = "serde", derive(Serialize))]
+/// U+200D ZERO WIDTH JOINER, which glues emoji into a single ZWJ sequence such as 👩‍💻.
+pub const ZWJ: char = '\u{200d}';
+
+/// `s` folded for search: lowercase, without diacritics and with ligatures spelled out, e.g. `"coeur"` for `"Cœur"`.
+///
+/// Search tokens and queries are both folded, so that either spelling finds the other.
+#[must_use]
+pub fn fold(s: &str) -> String {
+    fold_chars(s).collect()
+}
+
+/// The chars [`fold`] collects, for comparisons that need not allocate.
+pub fn fold_chars(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars()
+        .flat_map(char::to_lowercase)
+        .flat_map(fold_char)
+        .flatten()
+}
+
+/// The lowercase `c` without diacritics, as up to two chars for ligatures. Combining marks, as in decomposed text,
+/// are dropped.
+const fn fold_char(c: char) -> [Option<char>; 2] {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'æ' => return [Some('a'), Some('e')],
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ﬁ' => return [Some('f'), Some('i')],
+        'ﬂ' => return [Some('f'), Some('l')],
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĳ' => return [Some('i'), Some('j')],
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'œ' => return [Some('o'), Some('e')],
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ß' => return [Some('s'), Some('s')],
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        '\u{300}'..='\u{36f}' => return [None, None],
+        c => c,
+    };
+    [Some(folded), None]
+}
+
+impl<S, C> Emoji<S, C>
+where
+    S: AsRef<str> + Clone,
+    C: AsRef<[S]> + Clone,
+{
+    /// The emoji joined into this ZWJ sequence, e.g. 👩🏽 and 💻 for 👩🏽‍💻, or just the emoji itself when it is not one.
+    pub fn components(&self) -> impl Iterator<Item = &str> {
+        self.entry.emoji.as_ref().split(ZWJ)
+    }
+
+    /// Whether the emoji, its name, subgroup, tags or aliases contain `q`, ignoring case.
+    #[deprecated(note = "use `emojeez::search`, which ranks matches over a token index")]
+    #[must_use]
+    pub fn matches_search(&self, q: &str) -> bool {
+        if q.is_empty() {
+            return true;
+        }
+        let q = q.to_lowercase();
+        let contains = |s: &S| s.as_ref().to_lowercase().contains(&q);
+        contains(&self.entry.emoji)
+            || contains(&self.entry.name)
+            || contains(&self.entry.subgroup)
+            || self.entry.tags.as_ref().iter().any(contains)
+            || self.entry.aliases.as_ref().iter().any(contains)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Status {
     /// A qualified emoji character, or an emoji sequence in which each emoji character is qualified.
@@ -98,6 +213,27 @@ pub enum Status {
     Component,
 }
 
+/// How an RGI emoji is encoded, as listed in emoji-sequences.txt and emoji-zwj-sequences.txt.
+///
+/// <https://www.unicode.org/reports/tr51/#Emoji_Sets>
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SequenceKind {
+    /// A single code point, possibly followed by U+FE0F, e.g. 😀 or ☺️.
+    #[default]
+    Basic,
+    /// A digit, `#` or `*` followed by U+FE0F and U+20E3 COMBINING ENCLOSING KEYCAP, e.g. 1️⃣.
+    Keycap,
+    /// A pair of regional indicators, e.g. 🇩🇪.
+    Flag,
+    /// A black flag followed by tag characters, e.g. 🏴󠁧󠁢󠁳󠁣󠁴󠁿.
+    Tag,
+    /// An emoji followed by a skin tone modifier, e.g. 👋🏽.
+    Modifier,
+    /// Emoji joined by U+200D ZERO WIDTH JOINER, e.g. 👩‍💻.
+    Zwj,
+}
+
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 /// Five symbol modifier characters that provide for a range of skin tones for human emoji were released in Unicode Version 8.0.
@@ -161,6 +297,17 @@ impl Serialize for Group {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Group {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::try_from(s.as_str()).map_err(de::Error::custom)
+    }
+}
+
 impl TryFrom<String> for Group {
     type Error = String;
 
@@ -211,35 +358,97 @@ impl Group {
     }
 }
 
-impl<S, C> Emoji<S, C>
-where
-    S: AsRef<str> + Clone,
-    C: AsRef<[S]> + Clone,
-{
-    pub fn matches_search(&self, q: &str) -> bool {
-        if q.is_empty() {
-            return true;
-        }
-        let q = q.to_lowercase();
-        if self.entry.emoji.as_ref().to_lowercase().contains(&q) {
-            return true;
-        }
-        if self.entry.name.as_ref().to_lowercase().contains(&q) {
-            return true;
-        }
-        if self.entry.subgroup.as_ref().to_lowercase().contains(&q) {
-            return true;
-        }
-        for t in self.entry.tags.as_ref() {
-            if t.as_ref().to_lowercase().contains(&q) {
-                return true;
-            }
-        }
-        for a in self.entry.aliases.as_ref() {
-            if a.as_ref().to_lowercase().contains(&q) {
-                return true;
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type OwnedEmoji = Emoji<String, Vec<String>>;
+
+    fn waving_hand() -> OwnedEmoji {
+        Emoji {
+            entry: Entry {
+                group: Group::PeopleAndBody,
+                subgroup: "hand-fingers-open".to_owned(),
+                status: Status::FullyQualified,
+                sequence_kind: SequenceKind::Basic,
+                unicode_version: Version { major: 0, minor: 6 },
+                emoji: "👋".to_owned(),
+                name: "waving hand".to_owned(),
+                ios_version: Some(Version { major: 6, minor: 0 }),
+                tags: vec!["goodbye".to_owned()],
+                aliases: vec!["wave".to_owned()],
+            },
+            skin_tones: 6,
+            skin_tone: Some(SkinTone::Default),
+            variations: Vec::new(),
         }
-        false
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_emoji_round_trip() {
+        let emoji = waving_hand();
+
+        let json = serde_json::to_string(&emoji).unwrap();
+        assert!(json.contains(r#""group":"People & Body""#));
+        assert_eq!(serde_json::from_str::<OwnedEmoji>(&json).unwrap(), emoji);
+
+        let json = json.replace(r#""sequence_kind":"Basic","#, "");
+        assert!(!json.contains("sequence_kind"));
+        assert_eq!(serde_json::from_str::<OwnedEmoji>(&json).unwrap(), emoji);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_matches_search() {
+        let emoji = waving_hand();
+        assert!(emoji.matches_search(""));
+        assert!(emoji.matches_search("Waving"));
+        assert!(emoji.matches_search("fingers"));
+        assert!(emoji.matches_search("bye"));
+        assert!(emoji.matches_search("wave"));
+        assert!(!emoji.matches_search("smile"));
+    }
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("Cœur"), "coeur");
+        assert_eq!(
+            fold("Gesicht mit Freudentränen"),
+            "gesicht mit freudentranen"
+        );
+        assert_eq!(fold("Straße"), "strasse");
+        assert_eq!(fold("ÉLÈVE"), "eleve");
+        // Decomposed: e followed by U+0301 COMBINING ACUTE ACCENT
+        assert_eq!(fold("e\u{301}toile"), "etoile");
+        assert_eq!(fold("👋🏽"), "👋🏽");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_version_formats() {
+        let version = Version {
+            major: 15,
+            minor: 1,
+        };
+        assert_eq!(
+            serde_json::from_str::<Version>(r#""15.1""#).unwrap(),
+            version
+        );
+        assert_eq!(
+            serde_json::from_str::<Version>(r#"{"major":15,"minor":1}"#).unwrap(),
+            version
+        );
+        assert!(serde_json::from_str::<Version>(r#""15""#).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_group_as_str() {
+        assert_eq!(
+            serde_json::from_str::<Group>(r#""Food & Drink""#).unwrap(),
+            Group::FoodAndDrink
+        );
+        assert!(serde_json::from_str::<Group>(r#""FoodAndDrink""#).is_err());
     }
 }